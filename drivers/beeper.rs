@@ -0,0 +1,56 @@
+use embedded_hal::digital::v2::OutputPin;
+
+/// Active buzzer that sounds while its pin is high
+pub struct Beeper<Pin> {
+    pin: Pin,
+    enabled: bool,
+    /// Frames left to sound
+    frames: u32,
+}
+
+impl<Pin> Beeper<Pin>
+where
+    Pin: OutputPin,
+{
+    pub fn new(pin: Pin) -> Self {
+        Self {
+            pin,
+            enabled: true,
+            frames: 0,
+        }
+    }
+
+    pub fn release(self) -> Pin {
+        self.pin
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// A disabled beeper stops right away and ignores beeps
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.frames = 0;
+        }
+    }
+
+    /// Sounds for `frames` frames, a longer beep that is still sounding is
+    /// kept
+    pub fn beep(&mut self, frames: u32) {
+        if self.enabled {
+            self.frames = self.frames.max(frames);
+        }
+    }
+
+    /// Call once per frame
+    pub fn update(&mut self) -> Result<(), Pin::Error> {
+        if self.frames > 0 {
+            self.frames -= 1;
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        }
+    }
+}
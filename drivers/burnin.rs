@@ -0,0 +1,140 @@
+/// Timeouts are given in frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BurnInConfig {
+    /// Frames between moves of the whole picture, 0 to never move it
+    pub shift_period: u32,
+    /// Largest distance the picture is moved in pixels
+    pub max_shift: i32,
+    pub contrast: u8,
+    pub dim_contrast: u8,
+    /// Frames without input before the contrast is lowered, 0 to never dim
+    pub dim_after: u32,
+    /// Frames without input before the screensaver starts, 0 to never start
+    /// it
+    pub screensaver_after: u32,
+}
+
+impl BurnInConfig {
+    /// Timeouts for the given frame rate: shift every 3 minutes, dim after
+    /// a minute and start the screensaver after 5 minutes
+    pub const fn with_frame_rate(frame_rate: u32) -> Self {
+        Self {
+            shift_period: 3 * 60 * frame_rate,
+            max_shift: 1,
+            contrast: 0xcf,
+            dim_contrast: 0x08,
+            dim_after: 60 * frame_rate,
+            screensaver_after: 5 * 60 * frame_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idle {
+    Active,
+    Dimmed,
+    Screensaver,
+}
+
+/// Keeps track of time and input to protect OLED displays from burn-in
+#[derive(Debug, Clone, Copy)]
+pub struct BurnIn {
+    config: BurnInConfig,
+    frame: u32,
+    idle_frames: u32,
+}
+
+impl BurnIn {
+    pub const fn new(config: BurnInConfig) -> Self {
+        Self {
+            config,
+            frame: 0,
+            idle_frames: 0,
+        }
+    }
+
+    pub fn config(&self) -> BurnInConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: BurnInConfig) {
+        self.config = config;
+    }
+
+    /// Call once per frame, `active` tells whether a player used any control
+    pub fn next(&mut self, active: bool) {
+        self.frame = self.frame.wrapping_add(1);
+        self.idle_frames = if active {
+            0
+        } else {
+            self.idle_frames.saturating_add(1)
+        };
+    }
+
+    pub fn idle(&self) -> Idle {
+        let BurnInConfig {
+            dim_after,
+            screensaver_after,
+            ..
+        } = self.config;
+
+        if screensaver_after > 0 && self.idle_frames >= screensaver_after {
+            Idle::Screensaver
+        } else if dim_after > 0 && self.idle_frames >= dim_after {
+            Idle::Dimmed
+        } else {
+            Idle::Active
+        }
+    }
+
+    pub fn contrast(&self) -> u8 {
+        match self.idle() {
+            Idle::Active => self.config.contrast,
+            Idle::Dimmed | Idle::Screensaver => self.config.dim_contrast,
+        }
+    }
+
+    /// Offset of the whole picture, walks over a square of `max_shift`
+    /// pixels around the origin
+    pub fn shift(&self) -> (i32, i32) {
+        let BurnInConfig {
+            shift_period,
+            max_shift,
+            ..
+        } = self.config;
+
+        if shift_period == 0 || max_shift <= 0 {
+            return (0, 0);
+        }
+
+        let side = (2 * max_shift + 1) as u32;
+        let step = self.frame / shift_period % (side * side);
+        (
+            (step % side) as i32 - max_shift,
+            (step / side) as i32 - max_shift,
+        )
+    }
+
+    /// Position of something bouncing around in an area of `range` pixels
+    /// while the screensaver runs
+    pub fn screensaver_position(&self, range: (u32, u32)) -> (i32, i32) {
+        (
+            bounce(self.idle_frames, range.0),
+            bounce(self.idle_frames / 2, range.1),
+        )
+    }
+}
+
+/// Triangle wave between 0 and `range`
+fn bounce(time: u32, range: u32) -> i32 {
+    if range == 0 {
+        return 0;
+    }
+
+    let time = time % (2 * range);
+    if time < range {
+        time as i32
+    } else {
+        (2 * range - time) as i32
+    }
+}
@@ -0,0 +1,410 @@
+use {
+    core::{cell::RefCell, marker::PhantomData},
+    embedded_hal::{
+        adc::{Channel, OneShot},
+        digital::v2::InputPin,
+        Qei,
+    },
+};
+
+/// Buttons that come with a control, held down when true
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ControlKeys {
+    /// Works like the shared key: serve, continue
+    pub action: bool,
+    pub pause: bool,
+}
+
+pub trait PlayerControl {
+    /// Motion since the last call
+    fn delta(&mut self) -> i32;
+
+    /// Called as often as possible between frames by controls that sample
+    /// their inputs in software
+    fn poll(&mut self) {}
+
+    /// Buttons as seen by the last call of `delta`
+    fn keys(&self) -> ControlKeys {
+        ControlKeys::default()
+    }
+}
+
+pub struct PlayerEncoder<Counter>
+where
+    Counter: Qei<Count = u16>,
+{
+    prev: u16,
+    encoder: Counter,
+}
+
+impl<Counter> PlayerEncoder<Counter>
+where
+    Counter: Qei<Count = u16>,
+{
+    pub fn new(encoder: Counter) -> Self {
+        let prev = encoder.count();
+        Self { encoder, prev }
+    }
+}
+
+impl<Counter> PlayerControl for PlayerEncoder<Counter>
+where
+    Counter: Qei<Count = u16>,
+{
+    fn delta(&mut self) -> i32 {
+        let cnt = self.encoder.count();
+        let out = cnt.wrapping_sub(self.prev) as i16;
+        self.prev = cnt;
+
+        out as i32
+    }
+}
+
+/// Weight of a new sample in the filtered potentiometer value, 1 / N
+const POT_FILTER: i32 = 4;
+
+/// Quarters of a count the potentiometer has to move past a boundary before
+/// the position changes
+const POT_HYSTERESIS: i32 = 1;
+
+/// Lets several controls read channels of one ADC
+pub struct SharedAdc<'a, Adc>(pub &'a RefCell<Adc>);
+
+impl<'a, Adc, Unit, Word, Pin> OneShot<Unit, Word, Pin> for SharedAdc<'a, Adc>
+where
+    Adc: OneShot<Unit, Word, Pin>,
+    Pin: Channel<Unit>,
+{
+    type Error = Adc::Error;
+
+    fn read(&mut self, pin: &mut Pin) -> nb::Result<Word, Self::Error> {
+        self.0.borrow_mut().read(pin)
+    }
+}
+
+/// Potentiometer read through an ADC, the full range is `counts` counts
+pub struct Potentiometer<Adc, Unit, Pin> {
+    adc: Adc,
+    pin: Pin,
+    full_scale: u16,
+    counts: u32,
+    /// Sixteenths of an ADC step
+    filtered: Option<i32>,
+    position: i32,
+    unit: PhantomData<Unit>,
+}
+
+impl<Adc, Unit, Pin> Potentiometer<Adc, Unit, Pin>
+where
+    Adc: OneShot<Unit, u16, Pin>,
+    Pin: Channel<Unit>,
+{
+    /// `full_scale` is the largest value of the ADC
+    pub fn new(adc: Adc, pin: Pin, full_scale: u16, counts: u32) -> Self {
+        Self {
+            adc,
+            pin,
+            full_scale,
+            counts,
+            filtered: None,
+            position: 0,
+            unit: PhantomData,
+        }
+    }
+
+    pub fn release(self) -> (Adc, Pin) {
+        (self.adc, self.pin)
+    }
+}
+
+impl<Adc, Unit, Pin> PlayerControl for Potentiometer<Adc, Unit, Pin>
+where
+    Adc: OneShot<Unit, u16, Pin>,
+    Pin: Channel<Unit>,
+{
+    fn delta(&mut self) -> i32 {
+        let sample = match nb::block!(self.adc.read(&mut self.pin)) {
+            Ok(sample) => sample.min(self.full_scale) as i32 * 16,
+            Err(_) => return 0,
+        };
+
+        let filtered = match self.filtered {
+            Some(filtered) => filtered + (sample - filtered) / POT_FILTER,
+            None => sample,
+        };
+        let first = self.filtered.is_none();
+        self.filtered = Some(filtered);
+
+        let range = (self.full_scale as i64 + 1) * 16;
+        let quarters = (filtered as i64 * self.counts as i64 * 4 / range) as i32;
+        let lower = self.position * 4 - POT_HYSTERESIS;
+        let upper = (self.position + 1) * 4 + POT_HYSTERESIS;
+
+        if first || quarters < lower || quarters >= upper {
+            let position = quarters / 4;
+            let delta = if first { 0 } else { position - self.position };
+            self.position = position;
+            delta
+        } else {
+            0
+        }
+    }
+}
+
+/// Speeds of button controls in hundredths of a step per frame
+pub(crate) const BUTTON_SPEED: i32 = 100;
+pub(crate) const BUTTON_ACCELERATION: i32 = 5;
+pub(crate) const BUTTON_MAX_SPEED: i32 = 400;
+
+/// Up and down buttons, pressed when low. Holding a button speeds up the
+/// paddle
+pub struct Buttons<Up, Down> {
+    up: Up,
+    down: Down,
+    direction: i32,
+    held: i32,
+    /// Hundredths of a step
+    remainder: i32,
+}
+
+impl<Up, Down> Buttons<Up, Down>
+where
+    Up: InputPin,
+    Down: InputPin,
+{
+    pub fn new(up: Up, down: Down) -> Self {
+        Self {
+            up,
+            down,
+            direction: 0,
+            held: 0,
+            remainder: 0,
+        }
+    }
+
+    pub fn release(self) -> (Up, Down) {
+        (self.up, self.down)
+    }
+}
+
+impl<Up, Down> PlayerControl for Buttons<Up, Down>
+where
+    Up: InputPin,
+    Down: InputPin,
+{
+    fn delta(&mut self) -> i32 {
+        let up = self.up.is_low().unwrap_or(false);
+        let down = self.down.is_low().unwrap_or(false);
+        let direction = up as i32 - down as i32;
+
+        if direction != self.direction {
+            self.direction = direction;
+            self.held = 0;
+            self.remainder = 0;
+        }
+        if direction == 0 {
+            return 0;
+        }
+
+        let speed = (BUTTON_SPEED + BUTTON_ACCELERATION * self.held).min(BUTTON_MAX_SPEED);
+        self.held = self.held.saturating_add(1);
+
+        self.remainder += direction * speed;
+        let steps = self.remainder / 100;
+        self.remainder -= steps * 100;
+        steps
+    }
+}
+
+/// Adds a switch, pressed when low, as the action button of another control,
+/// like the push switch of most encoder modules
+pub struct WithButton<Control, Pin> {
+    control: Control,
+    pin: Pin,
+    down: bool,
+}
+
+impl<Control, Pin> WithButton<Control, Pin>
+where
+    Control: PlayerControl,
+    Pin: InputPin,
+{
+    pub fn new(control: Control, pin: Pin) -> Self {
+        Self {
+            control,
+            pin,
+            down: false,
+        }
+    }
+
+    pub fn release(self) -> (Control, Pin) {
+        (self.control, self.pin)
+    }
+}
+
+impl<Control, Pin> PlayerControl for WithButton<Control, Pin>
+where
+    Control: PlayerControl,
+    Pin: InputPin,
+{
+    fn delta(&mut self) -> i32 {
+        self.down = self.pin.is_low().unwrap_or(false);
+        self.control.delta()
+    }
+
+    fn poll(&mut self) {
+        self.control.poll();
+    }
+
+    fn keys(&self) -> ControlKeys {
+        let keys = self.control.keys();
+        ControlKeys {
+            action: keys.action || self.down,
+            ..keys
+        }
+    }
+}
+
+/// How encoder counts turn into paddle steps, percentages are used so the
+/// values are easy to store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sensitivity {
+    /// Paddle steps per 100 counts
+    pub scale: u16,
+    /// Percent added to the scale for every count moved in one frame
+    pub acceleration: u8,
+    pub invert: bool,
+    /// Counts per frame that are ignored as jitter
+    pub dead_zone: u8,
+}
+
+impl Default for Sensitivity {
+    fn default() -> Self {
+        Self::RAW
+    }
+}
+
+impl Sensitivity {
+    /// One step per count
+    pub const RAW: Self = Self {
+        scale: 100,
+        acceleration: 0,
+        invert: false,
+        dead_zone: 0,
+    };
+
+    pub const SIZE: usize = 5;
+
+    /// Scale and direction so that turning by `counts` moves the paddle
+    /// `steps` steps up, other values are kept
+    pub fn calibrated(self, counts: i32, steps: u32) -> Self {
+        if counts == 0 {
+            return self;
+        }
+
+        let scale = steps * 100 / counts.unsigned_abs();
+        Self {
+            scale: scale.clamp(1, u16::MAX as u32) as u16,
+            invert: counts < 0,
+            ..self
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; Self::SIZE] {
+        let [low, high] = self.scale.to_le_bytes();
+        [
+            low,
+            high,
+            self.acceleration,
+            self.invert as u8,
+            self.dead_zone,
+        ]
+    }
+
+    pub fn from_bytes(bytes: [u8; Self::SIZE]) -> Self {
+        Self {
+            scale: u16::from_le_bytes([bytes[0], bytes[1]]),
+            acceleration: bytes[2],
+            invert: bytes[3] != 0,
+            dead_zone: bytes[4],
+        }
+    }
+}
+
+/// Applies a `Sensitivity` to another control, fractions of a step are kept
+/// for the next frames
+pub struct Scaled<Control> {
+    control: Control,
+    sensitivity: Sensitivity,
+    /// Hundredths of a step
+    remainder: i32,
+}
+
+impl<Control> Scaled<Control>
+where
+    Control: PlayerControl,
+{
+    pub fn new(control: Control, sensitivity: Sensitivity) -> Self {
+        Self {
+            control,
+            sensitivity,
+            remainder: 0,
+        }
+    }
+
+    pub fn sensitivity(&self) -> Sensitivity {
+        self.sensitivity
+    }
+
+    pub fn set_sensitivity(&mut self, sensitivity: Sensitivity) {
+        self.sensitivity = sensitivity;
+        self.remainder = 0;
+    }
+
+    pub fn release(self) -> Control {
+        self.control
+    }
+}
+
+impl<Control> PlayerControl for Scaled<Control>
+where
+    Control: PlayerControl,
+{
+    fn delta(&mut self) -> i32 {
+        let Sensitivity {
+            scale,
+            acceleration,
+            invert,
+            dead_zone,
+        } = self.sensitivity;
+
+        let counts = self.control.delta();
+        if counts.abs() <= dead_zone as i32 {
+            return 0;
+        }
+
+        // a fraction left from turning the other way is dropped
+        if counts.signum() * self.remainder.signum() < 0 {
+            self.remainder = 0;
+        }
+
+        let percent = scale as i32 * (100 + acceleration as i32 * counts.abs()) / 100;
+        self.remainder += counts * percent;
+        let steps = self.remainder / 100;
+        self.remainder -= steps * 100;
+
+        if invert {
+            -steps
+        } else {
+            steps
+        }
+    }
+
+    fn poll(&mut self) {
+        self.control.poll();
+    }
+
+    fn keys(&self) -> ControlKeys {
+        self.control.keys()
+    }
+}
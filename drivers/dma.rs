@@ -0,0 +1,130 @@
+use {
+    crate::paged::Pages,
+    core::convert::Infallible,
+    embedded_graphics::{pixelcolor::BinaryColor, prelude::*},
+};
+
+/// Sends buffers to the display in the background
+pub trait WriteDma {
+    /// Waits until the previous transfer is complete, starts sending
+    /// `buffer` and returns the buffer of the previous transfer
+    fn swap(&mut self, buffer: &'static mut [u8]) -> &'static mut [u8];
+
+    /// `true` while a transfer is running
+    fn is_busy(&mut self) -> bool;
+
+    /// Waits until the previous transfer is complete and sends `bytes` as
+    /// commands, the display is back in data mode when it returns
+    fn command(&mut self, bytes: &[u8]);
+}
+
+/// Double buffered display with page memory layout; a frame is drawn into
+/// the back buffer while the front buffer is sent, the whole frame is sent
+/// every time so the display has to be set to horizontal addressing over
+/// its full area
+pub struct DmaDisplay<W>
+where
+    W: WriteDma,
+{
+    writer: W,
+    pages: Pages,
+    back: Option<&'static mut [u8]>,
+    contrast: Option<u8>,
+}
+
+impl<W> DmaDisplay<W>
+where
+    W: WriteDma,
+{
+    /// `back` and the buffer `writer` was created with have to hold at least
+    /// `width * height / 8` bytes
+    pub fn new(
+        writer: W,
+        back: &'static mut [u8],
+        width: u8,
+        height: u8,
+        transposed: bool,
+    ) -> Self {
+        let pages = Pages::new(width, height, transposed);
+        assert!(back.len() >= pages.len());
+
+        Self {
+            writer,
+            pages,
+            back: Some(back),
+            contrast: None,
+        }
+    }
+
+    pub fn release(self) -> W {
+        self.writer
+    }
+
+    /// Starts sending the drawn frame, the next frame is drawn into the
+    /// buffer of the previous transfer once it is complete, returns the
+    /// number of bytes handed to the DMA
+    pub fn flush(&mut self) -> usize {
+        let len = self.pages.len();
+        match self.back.take() {
+            Some(back) => {
+                let front = self.writer.swap(&mut back[..len]);
+                self.back = Some(front);
+                len
+            }
+            None => 0,
+        }
+    }
+
+    /// Moves everything drawn from now on by a number of pixels
+    pub fn set_shift(&mut self, shift: (i32, i32)) {
+        self.pages.shift = shift;
+    }
+
+    /// Sends the contrast command if the contrast changed
+    pub fn set_contrast(&mut self, contrast: u8) {
+        if self.contrast != Some(contrast) {
+            self.writer.command(&[0x81, contrast]);
+            self.contrast = Some(contrast);
+        }
+    }
+
+    pub fn is_flushing(&mut self) -> bool {
+        self.writer.is_busy()
+    }
+
+    fn buffer(&mut self) -> &mut [u8] {
+        self.back.as_deref_mut().unwrap_or(&mut [])
+    }
+}
+
+impl<W> DrawTarget for DmaDisplay<W>
+where
+    W: WriteDma,
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let pages = self.pages;
+        pages.draw(self.buffer(), pixels);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let pages = self.pages;
+        pages.clear(self.buffer(), color);
+        Ok(())
+    }
+}
+
+impl<W> OriginDimensions for DmaDisplay<W>
+where
+    W: WriteDma,
+{
+    fn size(&self) -> Size {
+        self.pages.size()
+    }
+}
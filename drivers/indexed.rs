@@ -0,0 +1,281 @@
+use {
+    core::convert::Infallible,
+    embedded_graphics::{
+        pixelcolor::{raw::RawU16, Rgb565},
+        prelude::*,
+        Pixel,
+    },
+};
+
+/// 160x128 in either orientation
+const MAX_SIDE: usize = 160;
+const MAX_PIXELS: usize = 160 * 128;
+
+/// Two pixels per byte
+const BUFFER_SIZE: usize = MAX_PIXELS / 2;
+
+/// Pixels along a side of the squares that are compared and sent as a whole
+const TILE: usize = 8;
+const MAX_TILES: usize = MAX_PIXELS / (TILE * TILE);
+
+/// Different colors a frame can have
+pub const PALETTE_SIZE: usize = 16;
+
+/// Colour panel with its own memory that takes rectangles of RGB565 pixels,
+/// like the ST7735
+pub trait PixelPanel {
+    type Error;
+
+    /// Fills the rectangle from `start` to `end`, both included, row by row
+    fn set_pixels<P>(
+        &mut self,
+        start: (u16, u16),
+        end: (u16, u16),
+        colors: P,
+    ) -> Result<(), Self::Error>
+    where
+        P: IntoIterator<Item = u16>;
+}
+
+/// Memory of an `IndexedDisplay`, about 12 KiB that should be kept in a
+/// static rather than moved around on the stack
+pub struct Frame {
+    buffer: [u8; BUFFER_SIZE],
+    shown: [u32; MAX_TILES],
+}
+
+impl Frame {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; BUFFER_SIZE],
+            shown: [0; MAX_TILES],
+        }
+    }
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Frame buffer for colour panels with 4 bits per pixel, a full RGB565
+/// frame doesn't fit in the RAM. Colors get an entry in a palette the first
+/// time they are drawn, colors past `PALETTE_SIZE` are drawn in the last
+/// one. A checksum of every tile that was sent is kept, a flush sends only
+/// the tiles that changed since
+pub struct IndexedDisplay<Panel> {
+    panel: Panel,
+    width: usize,
+    height: usize,
+    palette: [Rgb565; PALETTE_SIZE],
+    colors: usize,
+    frame: &'static mut Frame,
+    synced: bool,
+    flushed: usize,
+}
+
+impl<Panel> IndexedDisplay<Panel>
+where
+    Panel: PixelPanel,
+{
+    pub fn new(panel: Panel, frame: &'static mut Frame, width: u16, height: u16) -> Self {
+        let width = (width as usize).min(MAX_SIDE);
+        let rows = MAX_PIXELS / (tiles(width) * TILE) / TILE;
+
+        Self {
+            panel,
+            width,
+            height: (height as usize).min(rows * TILE),
+            palette: [Rgb565::BLACK; PALETTE_SIZE],
+            colors: 0,
+            frame,
+            synced: false,
+            flushed: 0,
+        }
+    }
+
+    pub fn release(self) -> Panel {
+        self.panel
+    }
+
+    /// Makes the next flush send the whole frame
+    pub fn invalidate(&mut self) {
+        self.synced = false;
+    }
+
+    /// Number of pixels sent by the last flush
+    pub fn flushed(&self) -> usize {
+        self.flushed
+    }
+
+    pub fn flush(&mut self) -> Result<usize, Panel::Error> {
+        let mut flushed = 0;
+        let columns = self.tile_columns();
+
+        for row in 0..self.tile_rows() {
+            let mut column = 0;
+            while column < columns {
+                if !self.update_tile(row, column) {
+                    column += 1;
+                    continue;
+                }
+
+                // neighbours that changed too go out in one rectangle
+                let start = column;
+                column += 1;
+                while column < columns && self.update_tile(row, column) {
+                    column += 1;
+                }
+
+                match self.send(row, start, column - 1) {
+                    Ok(pixels) => flushed += pixels,
+                    Err(error) => {
+                        self.synced = false;
+                        return Err(error);
+                    }
+                }
+            }
+        }
+
+        self.synced = true;
+        self.flushed = flushed;
+        Ok(flushed)
+    }
+
+    fn tile_columns(&self) -> usize {
+        tiles(self.width)
+    }
+
+    fn tile_rows(&self) -> usize {
+        tiles(self.height)
+    }
+
+    /// Remembers the checksum of a tile, returns whether it changed
+    fn update_tile(&mut self, row: usize, column: usize) -> bool {
+        let sum = self.checksum(row, column);
+        let tile = &mut self.frame.shown[row * self.tile_columns() + column];
+        let changed = !self.synced || *tile != sum;
+        *tile = sum;
+        changed
+    }
+
+    /// FNV-1a of the pixels of a tile
+    fn checksum(&self, row: usize, column: usize) -> u32 {
+        let mut sum = 0x811c_9dc5u32;
+        for y in row * TILE..((row + 1) * TILE).min(self.height) {
+            for x in column * TILE..((column + 1) * TILE).min(self.width) {
+                sum ^= self.index(x, y) as u32;
+                sum = sum.wrapping_mul(0x0100_0193);
+            }
+        }
+        sum
+    }
+
+    fn send(&mut self, row: usize, first: usize, last: usize) -> Result<usize, Panel::Error> {
+        let (left, right) = (first * TILE, ((last + 1) * TILE).min(self.width) - 1);
+        let (top, bottom) = (row * TILE, ((row + 1) * TILE).min(self.height) - 1);
+
+        let (buffer, palette, width) = (&self.frame.buffer, &self.palette, self.width);
+        let colors = (top..=bottom).flat_map(move |y| {
+            (left..=right).map(move |x| {
+                let index = pixel_index(buffer, width, x, y);
+                RawU16::from(palette[index as usize]).into_inner()
+            })
+        });
+        self.panel.set_pixels(
+            (left as u16, top as u16),
+            (right as u16, bottom as u16),
+            colors,
+        )?;
+
+        Ok((right - left + 1) * (bottom - top + 1))
+    }
+
+    fn index(&self, x: usize, y: usize) -> u8 {
+        pixel_index(&self.frame.buffer, self.width, x, y)
+    }
+
+    fn set_index(&mut self, x: usize, y: usize, index: u8) {
+        let pixel = y * self.width + x;
+        let byte = &mut self.frame.buffer[pixel / 2];
+        *byte = if pixel & 1 == 0 {
+            (*byte & 0xf0) | index
+        } else {
+            (*byte & 0x0f) | index << 4
+        };
+    }
+
+    /// Palette entry of a color, added if it's new
+    fn palette_index(&mut self, color: Rgb565) -> u8 {
+        if let Some(index) = self.palette[..self.colors].iter().position(|&c| c == color) {
+            return index as u8;
+        }
+        if self.colors < PALETTE_SIZE {
+            self.palette[self.colors] = color;
+            self.colors += 1;
+        }
+        (self.colors - 1) as u8
+    }
+}
+
+/// Tiles needed to cover a number of pixels
+fn tiles(pixels: usize) -> usize {
+    // TILE is a power of two
+    pixels / TILE + (pixels & (TILE - 1) > 0) as usize
+}
+
+fn pixel_index(buffer: &[u8], width: usize, x: usize, y: usize) -> u8 {
+    let pixel = y * width + x;
+    let byte = buffer[pixel / 2];
+    if pixel & 1 == 0 {
+        byte & 0x0f
+    } else {
+        byte >> 4
+    }
+}
+
+impl<Panel> OriginDimensions for IndexedDisplay<Panel>
+where
+    Panel: PixelPanel,
+{
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl<Panel> DrawTarget for IndexedDisplay<Panel>
+where
+    Panel: PixelPanel,
+{
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 {
+                continue;
+            }
+            let (x, y) = (point.x as usize, point.y as usize);
+            if x >= self.width || y >= self.height {
+                continue;
+            }
+
+            let index = self.palette_index(color);
+            self.set_index(x, y, index);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let index = self.palette_index(color);
+        self.frame
+            .buffer
+            .iter_mut()
+            .for_each(|byte| *byte = index | index << 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use {
+    crate::control::{ControlKeys, PlayerControl},
+    embedded_hal::digital::v2::InputPin,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    Press,
+    Release,
+    /// The key is still held after `KeyConfig::long_press` frames
+    LongPress,
+    /// A press shortly after a short press, reported instead of `Press`
+    DoublePress,
+}
+
+/// Timings are given in frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyConfig {
+    /// Samples in a row with the same level before a change is accepted
+    pub debounce: u32,
+    /// Frames the key is held before a long press, 0 to never report one
+    pub long_press: u32,
+    /// Frames after a release in which the next press is a double press, 0
+    /// to never report one
+    pub double_press: u32,
+}
+
+impl KeyConfig {
+    /// Timings for the given frame rate: about 30 ms of debouncing, a long
+    /// press after a second and double presses within 300 ms
+    pub const fn with_frame_rate(frame_rate: u32) -> Self {
+        Self {
+            debounce: frame_rate / 30 + 1,
+            long_press: frame_rate,
+            double_press: frame_rate * 3 / 10,
+        }
+    }
+}
+
+/// Turns raw samples of a key into debounced events
+#[derive(Debug, Clone, Copy)]
+pub struct Key {
+    config: KeyConfig,
+    raw: bool,
+    same: u32,
+    down: bool,
+    held: u32,
+    /// Frames since the release of a short press, which can still become a
+    /// double press
+    since_release: Option<u32>,
+    short: bool,
+}
+
+impl Key {
+    pub const fn new(config: KeyConfig) -> Self {
+        Self {
+            config,
+            raw: false,
+            same: 0,
+            down: false,
+            held: 0,
+            since_release: None,
+            short: false,
+        }
+    }
+
+    pub fn config(&self) -> KeyConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: KeyConfig) {
+        self.config = config;
+    }
+
+    /// Debounced level
+    pub fn is_down(&self) -> bool {
+        self.down
+    }
+
+    /// Call once per frame with the raw level of the key
+    pub fn update(&mut self, down: bool) -> Option<KeyEvent> {
+        let KeyConfig {
+            debounce,
+            long_press,
+            double_press,
+        } = self.config;
+
+        if down == self.raw {
+            self.same = self.same.saturating_add(1);
+        } else {
+            self.raw = down;
+            self.same = 1;
+        }
+
+        if self.raw != self.down && self.same >= debounce {
+            self.down = self.raw;
+
+            return if self.down {
+                let double = matches!(self.since_release, Some(frames) if frames < double_press);
+                self.held = 0;
+                self.since_release = None;
+                self.short = !double;
+                Some(if double {
+                    KeyEvent::DoublePress
+                } else {
+                    KeyEvent::Press
+                })
+            } else {
+                self.since_release = if self.short { Some(0) } else { None };
+                Some(KeyEvent::Release)
+            };
+        }
+
+        if self.down {
+            self.held = self.held.saturating_add(1);
+            if long_press > 0 && self.held == long_press {
+                self.short = false;
+                return Some(KeyEvent::LongPress);
+            }
+        } else if let Some(frames) = self.since_release.as_mut() {
+            *frames = frames.saturating_add(1);
+        }
+
+        None
+    }
+}
+
+/// Debounced buttons of one control
+#[derive(Debug, Clone, Copy)]
+struct PlayerKeys {
+    action: Key,
+    action_event: Option<KeyEvent>,
+    pause: Key,
+    pause_event: Option<KeyEvent>,
+}
+
+impl PlayerKeys {
+    const fn new(config: KeyConfig) -> Self {
+        Self {
+            action: Key::new(config),
+            action_event: None,
+            pause: Key::new(config),
+            pause_event: None,
+        }
+    }
+
+    fn update(&mut self, keys: ControlKeys) {
+        self.action_event = self.action.update(keys.action);
+        self.pause_event = self.pause.update(keys.pause);
+    }
+}
+
+fn is_press(event: Option<KeyEvent>) -> bool {
+    matches!(event, Some(KeyEvent::Press | KeyEvent::DoublePress))
+}
+
+/// Samples the key and the controls of both players once per frame. Motion
+/// is added up until it's taken, so nothing is lost while nobody reads it.
+/// Buttons of the controls are debounced like the key and reported for each
+/// player
+pub struct Input<Player1, Player2, Pin> {
+    player1: Player1,
+    player2: Player2,
+    /// Pressed when low
+    pin: Pin,
+    key: Key,
+    event: Option<KeyEvent>,
+    keys: (PlayerKeys, PlayerKeys),
+    motion: (i32, i32),
+}
+
+impl<Player1, Player2, Pin> Input<Player1, Player2, Pin>
+where
+    Player1: PlayerControl,
+    Player2: PlayerControl,
+    Pin: InputPin,
+{
+    pub fn new(player1: Player1, player2: Player2, pin: Pin, config: KeyConfig) -> Self {
+        Self {
+            player1,
+            player2,
+            pin,
+            key: Key::new(config),
+            event: None,
+            keys: (PlayerKeys::new(config), PlayerKeys::new(config)),
+            motion: (0, 0),
+        }
+    }
+
+    pub fn release(self) -> (Player1, Player2, Pin) {
+        (self.player1, self.player2, self.pin)
+    }
+
+    pub fn players(&mut self) -> (&mut Player1, &mut Player2) {
+        (&mut self.player1, &mut self.player2)
+    }
+
+    /// The shared key
+    pub fn key(&mut self) -> &mut Key {
+        &mut self.key
+    }
+
+    /// Call once per frame, returns whether a player used any control
+    pub fn sample(&mut self) -> Result<bool, Pin::Error> {
+        let delta1 = self.player1.delta();
+        let delta2 = self.player2.delta();
+        self.motion.0 += delta1;
+        self.motion.1 += delta2;
+
+        let keys1 = self.player1.keys();
+        let keys2 = self.player2.keys();
+        let down = self.pin.is_low()?;
+        self.event = self.key.update(down);
+        self.keys.0.update(keys1);
+        self.keys.1.update(keys2);
+
+        let buttons = keys1 != ControlKeys::default() || keys2 != ControlKeys::default();
+        Ok(down || buttons || delta1 != 0 || delta2 != 0)
+    }
+
+    /// Lets controls decoded in software catch every edge, call while
+    /// waiting for the next frame
+    pub fn poll(&mut self) {
+        self.player1.poll();
+        self.player2.poll();
+    }
+
+    /// Event of the shared key in the last sampled frame
+    pub fn event(&self) -> Option<KeyEvent> {
+        self.event
+    }
+
+    /// Events of the action buttons of both players in the last sampled
+    /// frame
+    pub fn player_events(&self) -> (Option<KeyEvent>, Option<KeyEvent>) {
+        (self.keys.0.action_event, self.keys.1.action_event)
+    }
+
+    /// Whether the shared key went down in the last sampled frame
+    pub fn key_pressed(&self) -> bool {
+        is_press(self.event)
+    }
+
+    /// Whether the action button of each player went down in the last
+    /// sampled frame
+    pub fn player_pressed(&self) -> (bool, bool) {
+        (
+            is_press(self.keys.0.action_event),
+            is_press(self.keys.1.action_event),
+        )
+    }
+
+    /// Whether the shared key or any action button went down in the last
+    /// sampled frame
+    pub fn pressed(&self) -> bool {
+        let (pressed1, pressed2) = self.player_pressed();
+        self.key_pressed() || pressed1 || pressed2
+    }
+
+    /// Whether a pause button of the controls went down in the last sampled
+    /// frame
+    pub fn pause_pressed(&self) -> bool {
+        is_press(self.keys.0.pause_event) || is_press(self.keys.1.pause_event)
+    }
+
+    /// Whether the shared key or any action button is held
+    pub fn key_down(&self) -> bool {
+        self.key.is_down() || self.keys.0.action.is_down() || self.keys.1.action.is_down()
+    }
+
+    /// Motion of both players that wasn't taken yet
+    pub fn motion(&self) -> (i32, i32) {
+        self.motion
+    }
+
+    pub fn take_motion(&mut self) -> (i32, i32) {
+        core::mem::take(&mut self.motion)
+    }
+}
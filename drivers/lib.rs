@@ -0,0 +1,18 @@
+#![cfg_attr(not(test), no_std)]
+
+pub mod beeper;
+pub mod burnin;
+pub mod control;
+pub mod dma;
+pub mod indexed;
+pub mod input;
+pub mod menu;
+pub mod nec;
+pub mod nunchuk;
+pub mod paged;
+pub mod quadrature;
+pub mod segments;
+pub mod storage;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,188 @@
+use numtoa::NumToA;
+
+/// Characters of text entries in the order they are turned through
+pub const TEXT_CHARS: &[u8] = b" ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+
+pub const TEXT_LEN: usize = 6;
+
+/// Controller steps from one entry or value to the next
+const STEPS_PER_ITEM: i32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    /// Confirming it is reported, e.g. to start the game
+    Action,
+    /// One of the labels in `options`
+    List {
+        options: &'static [&'static str],
+        index: usize,
+    },
+    Spinner {
+        value: i32,
+        min: i32,
+        max: i32,
+        step: i32,
+    },
+    Toggle(bool),
+    /// Characters of `TEXT_CHARS`, padded with spaces
+    Text([u8; TEXT_LEN]),
+}
+
+impl Item {
+    /// The value as shown to the player
+    pub fn text<'a>(&'a self, buffer: &'a mut [u8; 11]) -> &'a str {
+        match self {
+            Item::Action => "",
+            Item::List { options, index } => options.get(*index).copied().unwrap_or_default(),
+            Item::Spinner { value, .. } => value.numtoa_str(10, buffer),
+            Item::Toggle(true) => "ON",
+            Item::Toggle(false) => "OFF",
+            Item::Text(text) => core::str::from_utf8(text).unwrap_or_default(),
+        }
+    }
+
+    /// Moves the value by `steps`, lists and spinners stop at their ends
+    fn turn(&mut self, steps: i32, cursor: usize) {
+        match self {
+            Item::List { options, index } => {
+                let last = options.len().saturating_sub(1) as i32;
+                *index = (*index as i32 + steps).max(0).min(last) as usize;
+            }
+            Item::Spinner {
+                value,
+                min,
+                max,
+                step,
+            } => *value = (*value + steps * *step).max(*min).min(*max),
+            Item::Text(text) => {
+                let count = TEXT_CHARS.len() as i32;
+                let position = TEXT_CHARS
+                    .iter()
+                    .position(|&c| c == text[cursor])
+                    .unwrap_or(0) as i32;
+                text[cursor] = TEXT_CHARS[(position + steps).rem_euclid(count) as usize];
+            }
+            Item::Action | Item::Toggle(_) => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub label: &'static str,
+    pub item: Item,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// An action was confirmed
+    Activated(usize),
+    /// The value of an entry was changed and confirmed
+    Changed(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Turning moves between entries
+    Browsing,
+    /// Turning changes the value of the selected entry
+    Editing,
+    /// Turning changes the character at this position of the selected text
+    EditingText(usize),
+}
+
+/// List of entries navigated with the motion of a control and a key: press
+/// to edit the selected value and again to confirm it. Moving up selects
+/// the previous entry and increases values
+#[derive(Debug, Clone, Copy)]
+pub struct Menu<const N: usize> {
+    entries: [Entry; N],
+    selected: usize,
+    mode: Mode,
+    motion: i32,
+}
+
+impl<const N: usize> Menu<N> {
+    pub fn new(entries: [Entry; N]) -> Self {
+        Self {
+            entries,
+            selected: 0,
+            mode: Mode::Browsing,
+            motion: 0,
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry; N] {
+        &self.entries
+    }
+
+    pub fn item(&self, index: usize) -> Item {
+        self.entries[index].item
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Call once per frame with the motion of a control and whether the key
+    /// was pressed
+    pub fn update(&mut self, motion: i32, pressed: bool) -> Option<MenuEvent> {
+        self.motion += motion;
+        let steps = self.motion / STEPS_PER_ITEM;
+        self.motion -= steps * STEPS_PER_ITEM;
+
+        let selected = self.selected;
+        let item = &mut self.entries[selected].item;
+
+        match self.mode {
+            Mode::Browsing => {
+                let last = N.saturating_sub(1) as i32;
+                self.selected = (selected as i32 - steps).max(0).min(last) as usize;
+                if !pressed {
+                    return None;
+                }
+
+                let item = &mut self.entries[self.selected].item;
+                self.motion = 0;
+                match item {
+                    Item::Action => return Some(MenuEvent::Activated(self.selected)),
+                    Item::Toggle(on) => {
+                        *on = !*on;
+                        return Some(MenuEvent::Changed(self.selected));
+                    }
+                    Item::List { .. } | Item::Spinner { .. } => self.mode = Mode::Editing,
+                    Item::Text(_) => self.mode = Mode::EditingText(0),
+                }
+                None
+            }
+            Mode::Editing => {
+                item.turn(steps, 0);
+                if pressed {
+                    self.mode = Mode::Browsing;
+                    self.motion = 0;
+                    return Some(MenuEvent::Changed(selected));
+                }
+                None
+            }
+            Mode::EditingText(cursor) => {
+                item.turn(steps, cursor);
+                if !pressed {
+                    return None;
+                }
+
+                self.motion = 0;
+                if cursor + 1 < TEXT_LEN {
+                    self.mode = Mode::EditingText(cursor + 1);
+                    None
+                } else {
+                    self.mode = Mode::Browsing;
+                    Some(MenuEvent::Changed(selected))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use {
+    crate::control::{
+        ControlKeys, PlayerControl, BUTTON_ACCELERATION, BUTTON_MAX_SPEED, BUTTON_SPEED,
+    },
+    core::sync::atomic::{AtomicU32, Ordering},
+    embedded_hal::digital::v2::InputPin,
+};
+
+/// Nominal lengths of marks and spaces in microseconds
+const LEADER_MARK: u32 = 9000;
+const LEADER_SPACE: u32 = 4500;
+const REPEAT_SPACE: u32 = 2250;
+const BIT_MARK: u32 = 560;
+const ZERO_SPACE: u32 = 560;
+const ONE_SPACE: u32 = 1690;
+
+/// Microseconds from the end of a frame or repeat to the end of the next
+/// repeat, remotes send one every 108 ms
+const REPEAT_TIMEOUT: u32 = 150_000;
+
+/// Frames a button counts as held after its last code or repeat, a bit more
+/// than the repeat period at 60 frames per second
+const HOLD_FRAMES: u32 = 8;
+
+/// Receivers and remotes are off by up to a quarter
+fn near(duration: u32, nominal: u32) -> bool {
+    duration >= nominal * 3 / 4 && duration <= nominal * 5 / 4
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NecCode {
+    /// 8 bit addresses of the original protocol, 16 bits for extended ones
+    pub address: u16,
+    pub command: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NecEvent {
+    Code(NecCode),
+    /// The button of the code is still held
+    Repeat(NecCode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    LeaderSpace,
+    RepeatMark,
+    /// Bits received so far
+    BitMark(u8),
+    BitSpace(u8),
+}
+
+/// Decodes NEC frames from the lengths of marks and spaces
+#[derive(Debug, Clone)]
+pub struct NecDecoder {
+    state: State,
+    bits: u32,
+    last: Option<NecCode>,
+    /// Microseconds since the last code or repeat
+    since_last: u32,
+}
+
+impl Default for NecDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NecDecoder {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Idle,
+            bits: 0,
+            last: None,
+            since_last: u32::MAX,
+        }
+    }
+
+    /// Call with every mark and space in microseconds, returns an event when
+    /// a frame or a repeat is complete. Anything unexpected waits for the
+    /// next leader
+    pub fn pulse(&mut self, mark: bool, duration: u32) -> Option<NecEvent> {
+        self.since_last = self.since_last.saturating_add(duration);
+
+        let (state, event) = match (self.state, mark) {
+            (_, true) if near(duration, LEADER_MARK) => (State::LeaderSpace, None),
+            (State::LeaderSpace, false) if near(duration, LEADER_SPACE) => {
+                self.bits = 0;
+                (State::BitMark(0), None)
+            }
+            (State::LeaderSpace, false) if near(duration, REPEAT_SPACE) => {
+                (State::RepeatMark, None)
+            }
+            (State::RepeatMark, true) if near(duration, BIT_MARK) => (State::Idle, self.repeat()),
+            (State::BitMark(bits), true) if near(duration, BIT_MARK) => {
+                (State::BitSpace(bits), None)
+            }
+            (State::BitSpace(bits), false)
+                if near(duration, ZERO_SPACE) || near(duration, ONE_SPACE) =>
+            {
+                if near(duration, ONE_SPACE) {
+                    self.bits |= 1 << bits;
+                }
+                if bits == 31 {
+                    (State::Idle, self.frame())
+                } else {
+                    (State::BitMark(bits + 1), None)
+                }
+            }
+            _ => (State::Idle, None),
+        };
+
+        self.state = state;
+        event
+    }
+
+    fn frame(&mut self) -> Option<NecEvent> {
+        // bits are sent least significant first: address, inverted address
+        // or its high byte, command, inverted command
+        let [address, high, command, inverted] = self.bits.to_le_bytes();
+        if command != !inverted {
+            self.last = None;
+            return None;
+        }
+
+        let address = if high == !address {
+            address as u16
+        } else {
+            u16::from_le_bytes([address, high])
+        };
+        let code = NecCode { address, command };
+        self.last = Some(code);
+        self.since_last = 0;
+        Some(NecEvent::Code(code))
+    }
+
+    fn repeat(&mut self) -> Option<NecEvent> {
+        let code = self.last.filter(|_| self.since_last <= REPEAT_TIMEOUT);
+        self.last = code;
+        self.since_last = 0;
+        code.map(NecEvent::Repeat)
+    }
+}
+
+/// Measures the pin of an IR receiver module, which is low while it sees the
+/// carrier
+pub struct NecReceiver<Pin> {
+    pin: Pin,
+    decoder: NecDecoder,
+    ticks_per_us: u32,
+    last_edge: Option<u32>,
+}
+
+impl<Pin> NecReceiver<Pin>
+where
+    Pin: InputPin,
+{
+    /// `ticks_per_us` is the rate of the tick count given to `edge`
+    pub fn new(pin: Pin, ticks_per_us: u32) -> Self {
+        Self {
+            pin,
+            decoder: NecDecoder::new(),
+            ticks_per_us: ticks_per_us.max(1),
+            last_edge: None,
+        }
+    }
+
+    pub fn pin(&mut self) -> &mut Pin {
+        &mut self.pin
+    }
+
+    pub fn release(self) -> Pin {
+        self.pin
+    }
+
+    /// Call on every edge of the pin with a free running tick count, which
+    /// may wrap
+    pub fn edge(&mut self, now: u32) -> Option<NecEvent> {
+        let previous = self.last_edge.replace(now)?;
+        let duration = now.wrapping_sub(previous) / self.ticks_per_us;
+        // the level before a rising edge was a mark
+        let mark = self.pin.is_high().ok()?;
+        self.decoder.pulse(mark, duration)
+    }
+}
+
+/// Events a receiver keeps for its players, plenty for both halves of a
+/// remote between two frames
+const QUEUE: usize = 8;
+
+/// Recent events of a receiver, written from its interrupt and read by any
+/// number of players at their own pace. Each event is packed into one word
+/// with a tag of its number, so it's never seen half written and a reader
+/// that fell behind notices it was overwritten
+pub struct RemoteCodes {
+    events: [AtomicU32; QUEUE],
+    pushed: AtomicU32,
+}
+
+impl Default for RemoteCodes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 0 is left for slots that were never written
+fn tag(index: u32) -> u32 {
+    index % 127 + 1
+}
+
+impl RemoteCodes {
+    pub const fn new() -> Self {
+        Self {
+            events: [
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+                AtomicU32::new(0),
+            ],
+            pushed: AtomicU32::new(0),
+        }
+    }
+
+    /// Only one receiver may push to a set of codes
+    pub fn push(&self, event: NecEvent) {
+        let index = self.pushed.load(Ordering::Relaxed);
+        let (code, repeat) = match event {
+            NecEvent::Code(code) => (code, 0),
+            NecEvent::Repeat(code) => (code, 1),
+        };
+        self.events[index as usize % QUEUE].store(
+            tag(index) << 25 | repeat << 24 | (code.address as u32) << 8 | code.command as u32,
+            Ordering::Release,
+        );
+        self.pushed.store(index.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Number of events pushed so far, wraps around
+    pub fn pushed(&self) -> u32 {
+        self.pushed.load(Ordering::Acquire)
+    }
+
+    /// Event number `index`, `None` if it wasn't pushed yet or has been
+    /// overwritten since
+    pub fn get(&self, index: u32) -> Option<NecEvent> {
+        let word = self.events[index as usize % QUEUE].load(Ordering::Acquire);
+        if word >> 25 != tag(index) {
+            return None;
+        }
+
+        let code = NecCode {
+            address: (word >> 8) as u16,
+            command: word as u8,
+        };
+        if word & 1 << 24 != 0 {
+            Some(NecEvent::Repeat(code))
+        } else {
+            Some(NecEvent::Code(code))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteButton {
+    Up,
+    Down,
+    Action,
+    Pause,
+}
+
+/// Commands of the buttons a player uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keymap {
+    /// Codes of other remotes are ignored, `None` takes any
+    pub address: Option<u16>,
+    pub up: u8,
+    pub down: u8,
+    pub action: u8,
+    pub pause: u8,
+}
+
+impl Keymap {
+    /// Common 21 button remotes: CH+ and CH- move, CH serves, play pauses
+    pub const LEFT: Self = Self {
+        address: Some(0x00),
+        up: 0x47,
+        down: 0x45,
+        action: 0x46,
+        pause: 0x43,
+    };
+
+    /// The same remotes: + and - move, EQ serves, play pauses
+    pub const RIGHT: Self = Self {
+        address: Some(0x00),
+        up: 0x15,
+        down: 0x07,
+        action: 0x09,
+        pause: 0x43,
+    };
+
+    pub fn button(&self, code: NecCode) -> Option<RemoteButton> {
+        if matches!(self.address, Some(address) if address != code.address) {
+            return None;
+        }
+
+        match code.command {
+            command if command == self.up => Some(RemoteButton::Up),
+            command if command == self.down => Some(RemoteButton::Down),
+            command if command == self.action => Some(RemoteButton::Action),
+            command if command == self.pause => Some(RemoteButton::Pause),
+            _ => None,
+        }
+    }
+}
+
+/// A player on an IR remote. A button stays held while its repeats come in,
+/// holding up or down speeds up the paddle like `Buttons`
+pub struct RemotePlayer<'a> {
+    codes: &'a RemoteCodes,
+    keymap: Keymap,
+    seen: u32,
+    button: Option<RemoteButton>,
+    /// Frames left until the button counts as released
+    hold: u32,
+    held: i32,
+    /// Hundredths of a step
+    remainder: i32,
+}
+
+impl<'a> RemotePlayer<'a> {
+    pub fn new(codes: &'a RemoteCodes, keymap: Keymap) -> Self {
+        Self {
+            codes,
+            keymap,
+            seen: codes.pushed(),
+            button: None,
+            hold: 0,
+            held: 0,
+            remainder: 0,
+        }
+    }
+
+    pub fn keymap(&self) -> Keymap {
+        self.keymap
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.press(None);
+    }
+
+    /// Button held as seen by the last call of `delta`
+    pub fn button(&self) -> Option<RemoteButton> {
+        self.button
+    }
+
+    fn press(&mut self, button: Option<RemoteButton>) {
+        self.button = button;
+        self.hold = if button.is_some() { HOLD_FRAMES } else { 0 };
+        self.held = 0;
+        self.remainder = 0;
+    }
+}
+
+impl<'a> PlayerControl for RemotePlayer<'a> {
+    fn delta(&mut self) -> i32 {
+        let pushed = self.codes.pushed();
+        if pushed.wrapping_sub(self.seen) > QUEUE as u32 {
+            self.seen = pushed.wrapping_sub(QUEUE as u32);
+        }
+
+        // codes of other remotes and of the other player's buttons are
+        // skipped, they mustn't release the button held here
+        let mut heard = false;
+        while self.seen != pushed {
+            let event = self.codes.get(self.seen);
+            self.seen = self.seen.wrapping_add(1);
+            match event {
+                Some(NecEvent::Code(code)) => {
+                    if let Some(button) = self.keymap.button(code) {
+                        self.press(Some(button));
+                        heard = true;
+                    }
+                }
+                Some(NecEvent::Repeat(code))
+                    if self.button.is_some() && self.keymap.button(code) == self.button =>
+                {
+                    self.hold = HOLD_FRAMES;
+                    heard = true;
+                }
+                _ => {}
+            }
+        }
+
+        if !heard && self.hold > 0 {
+            self.hold -= 1;
+            if self.hold == 0 {
+                self.press(None);
+            }
+        }
+
+        let direction = match self.button {
+            Some(RemoteButton::Up) => 1,
+            Some(RemoteButton::Down) => -1,
+            _ => return 0,
+        };
+
+        let speed = (BUTTON_SPEED + BUTTON_ACCELERATION * self.held).min(BUTTON_MAX_SPEED);
+        self.held = self.held.saturating_add(1);
+
+        self.remainder += direction * speed;
+        let steps = self.remainder / 100;
+        self.remainder -= steps * 100;
+        steps
+    }
+
+    fn keys(&self) -> ControlKeys {
+        ControlKeys {
+            action: self.button == Some(RemoteButton::Action),
+            pause: self.button == Some(RemoteButton::Pause),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use {
+    crate::control::{ControlKeys, PlayerControl},
+    embedded_hal::blocking::i2c::{Read, Write},
+};
+
+pub const ADDRESS: u8 = 0x52;
+
+/// Joystick deflection around the center that doesn't move the paddle
+const DEAD_ZONE: i32 = 12;
+
+/// Hundredths of a step per frame for each unit of deflection past the dead
+/// zone
+const SPEED: i32 = 3;
+
+/// Joystick centers further off are taken for a stick held at power on
+const CENTER_RANGE: (u8, u8) = (100, 156);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NunchukState {
+    /// Left and down are low, about 128 in the center
+    pub joystick: (u8, u8),
+    /// 10 bit values of the x, y and z axes
+    pub acceleration: (u16, u16, u16),
+    pub c: bool,
+    pub z: bool,
+}
+
+impl NunchukState {
+    pub fn from_bytes(bytes: [u8; 6]) -> Self {
+        let low = bytes[5];
+        Self {
+            joystick: (bytes[0], bytes[1]),
+            acceleration: (
+                (bytes[2] as u16) << 2 | (low >> 2 & 3) as u16,
+                (bytes[3] as u16) << 2 | (low >> 4 & 3) as u16,
+                (bytes[4] as u16) << 2 | (low >> 6 & 3) as u16,
+            ),
+            // buttons read 0 when pressed
+            z: low & 1 == 0,
+            c: low & 2 == 0,
+        }
+    }
+}
+
+/// Wii Nunchuk, the joystick moves the paddle, Z serves and C pauses
+pub struct Nunchuk<I2c> {
+    i2c: I2c,
+    /// Initialized and a measurement was requested
+    ready: bool,
+    state: NunchukState,
+    center: Option<u8>,
+    /// Hundredths of a step
+    remainder: i32,
+}
+
+impl<I2c, E> Nunchuk<I2c>
+where
+    I2c: Read<Error = E> + Write<Error = E>,
+{
+    pub fn new(i2c: I2c) -> Self {
+        Self {
+            i2c,
+            ready: false,
+            state: NunchukState::default(),
+            center: None,
+            remainder: 0,
+        }
+    }
+
+    pub fn release(self) -> I2c {
+        self.i2c
+    }
+
+    pub fn state(&self) -> NunchukState {
+        self.state
+    }
+
+    /// Reads the measurement requested by the previous call and requests the
+    /// next one, so the controller has a frame to measure. Initializes the
+    /// controller without encryption first, again after an error
+    pub fn update(&mut self) -> Result<NunchukState, E> {
+        let result = self.transfer();
+        if result.is_err() {
+            self.ready = false;
+        }
+        result
+    }
+
+    fn transfer(&mut self) -> Result<NunchukState, E> {
+        if !self.ready {
+            self.i2c.write(ADDRESS, &[0xf0, 0x55])?;
+            self.i2c.write(ADDRESS, &[0xfb, 0x00])?;
+            self.i2c.write(ADDRESS, &[0x00])?;
+            self.ready = true;
+            return Ok(self.state);
+        }
+
+        let mut bytes = [0; 6];
+        self.i2c.read(ADDRESS, &mut bytes)?;
+        self.i2c.write(ADDRESS, &[0x00])?;
+
+        // not initialized, e.g. plugged in again
+        if bytes == [0xff; 6] {
+            self.ready = false;
+            return Ok(self.state);
+        }
+
+        self.state = NunchukState::from_bytes(bytes);
+        if self.center.is_none() {
+            let y = self.state.joystick.1;
+            let centered = (CENTER_RANGE.0..=CENTER_RANGE.1).contains(&y);
+            self.center = Some(if centered { y } else { 128 });
+        }
+        Ok(self.state)
+    }
+}
+
+impl<I2c, E> PlayerControl for Nunchuk<I2c>
+where
+    I2c: Read<Error = E> + Write<Error = E>,
+{
+    fn delta(&mut self) -> i32 {
+        let (state, center) = match (self.update(), self.center) {
+            (Ok(state), Some(center)) => (state, center),
+            _ => {
+                self.remainder = 0;
+                return 0;
+            }
+        };
+
+        let deflection = state.joystick.1 as i32 - center as i32;
+        if deflection.abs() <= DEAD_ZONE {
+            self.remainder = 0;
+            return 0;
+        }
+
+        self.remainder += (deflection - DEAD_ZONE * deflection.signum()) * SPEED;
+        let steps = self.remainder / 100;
+        self.remainder -= steps * 100;
+        steps
+    }
+
+    fn keys(&self) -> ControlKeys {
+        ControlKeys {
+            action: self.state.z,
+            pause: self.state.c,
+        }
+    }
+}
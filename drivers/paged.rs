@@ -0,0 +1,238 @@
+use {
+    core::convert::Infallible,
+    display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand},
+    embedded_graphics::{pixelcolor::BinaryColor, prelude::*, Pixel},
+};
+
+const MAX_COLUMNS: usize = 128;
+const MAX_PAGES: usize = 8;
+const BUFFER_SIZE: usize = MAX_COLUMNS * MAX_PAGES;
+
+/// Changed columns closer than this are sent in one run, every run costs six
+/// command bytes
+const MERGE_GAP: usize = 6;
+
+const SET_COLUMN_ADDRESS: u8 = 0x21;
+const SET_PAGE_ADDRESS: u8 = 0x22;
+
+/// Frame buffer for displays with SSD1306 style memory, where every byte is
+/// a column of eight pixels in a page. It remembers what the display already
+/// shows and sends only the columns that changed since the last flush. The
+/// display must be in horizontal addressing mode
+pub struct PagedDisplay<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    interface: DI,
+    pages: Pages,
+    offset: (u8, u8),
+    buffer: [u8; BUFFER_SIZE],
+    shown: [u8; BUFFER_SIZE],
+    synced: bool,
+    flushed: usize,
+    contrast: Option<u8>,
+}
+
+impl<DI> PagedDisplay<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// `offset` is the first column and page of the panel in the display
+    /// memory, `transposed` swaps x and y for displays rotated by 90 or 270
+    /// degrees
+    pub fn new(interface: DI, width: u8, height: u8, offset: (u8, u8), transposed: bool) -> Self {
+        Self {
+            interface,
+            pages: Pages::new(width, height, transposed),
+            offset,
+            buffer: [0; BUFFER_SIZE],
+            shown: [0; BUFFER_SIZE],
+            synced: false,
+            flushed: 0,
+            contrast: None,
+        }
+    }
+
+    pub fn release(self) -> DI {
+        self.interface
+    }
+
+    /// Makes the next flush send the whole frame
+    pub fn invalidate(&mut self) {
+        self.synced = false;
+    }
+
+    /// Moves everything drawn from now on by a number of pixels
+    pub fn set_shift(&mut self, shift: (i32, i32)) {
+        self.pages.shift = shift;
+    }
+
+    /// Sends the contrast command if the contrast changed
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError> {
+        if self.contrast != Some(contrast) {
+            self.interface
+                .send_commands(DataFormat::U8(&[0x81, contrast]))?;
+            self.contrast = Some(contrast);
+        }
+        Ok(())
+    }
+
+    /// Number of data bytes sent by the last flush
+    pub fn flushed(&self) -> usize {
+        self.flushed
+    }
+
+    pub fn flush(&mut self) -> Result<usize, DisplayError> {
+        let mut flushed = 0;
+
+        let columns = self.pages.columns;
+
+        for page in 0..self.pages.pages {
+            let mut column = 0;
+            while let Some(start) = (column..columns).find(|&c| self.changed(page, c)) {
+                let mut end = start;
+                for next in start + 1..columns {
+                    if next - end > MERGE_GAP {
+                        break;
+                    }
+                    if self.changed(page, next) {
+                        end = next;
+                    }
+                }
+
+                self.send(page, start, end)?;
+                flushed += end - start + 1;
+                column = end + 1;
+            }
+        }
+
+        self.synced = true;
+        self.flushed = flushed;
+        Ok(flushed)
+    }
+
+    fn changed(&self, page: usize, column: usize) -> bool {
+        let index = page * self.pages.columns + column;
+        !self.synced || self.buffer[index] != self.shown[index]
+    }
+
+    fn send(&mut self, page: usize, start: usize, end: usize) -> Result<(), DisplayError> {
+        let (column_offset, page_offset) = self.offset;
+        let page_address = page as u8 + page_offset;
+
+        self.interface.send_commands(DataFormat::U8(&[
+            SET_COLUMN_ADDRESS,
+            start as u8 + column_offset,
+            end as u8 + column_offset,
+            SET_PAGE_ADDRESS,
+            page_address,
+            page_address,
+        ]))?;
+
+        let row = page * self.pages.columns;
+        let range = row + start..row + end + 1;
+        self.interface
+            .send_data(DataFormat::U8(&self.buffer[range.clone()]))?;
+        self.shown[range.clone()].copy_from_slice(&self.buffer[range]);
+
+        Ok(())
+    }
+}
+
+impl<DI> OriginDimensions for PagedDisplay<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    fn size(&self) -> Size {
+        self.pages.size()
+    }
+}
+
+impl<DI> DrawTarget for PagedDisplay<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.pages.draw(&mut self.buffer, pixels);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pages.clear(&mut self.buffer, color);
+        Ok(())
+    }
+}
+
+/// Layout of a buffer in display pages
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pages {
+    pub columns: usize,
+    pub pages: usize,
+    transposed: bool,
+    pub shift: (i32, i32),
+}
+
+impl Pages {
+    pub fn new(width: u8, height: u8, transposed: bool) -> Self {
+        Self {
+            columns: (width as usize).min(MAX_COLUMNS),
+            pages: (height as usize / 8).min(MAX_PAGES),
+            transposed,
+            shift: (0, 0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns * self.pages
+    }
+
+    pub fn size(&self) -> Size {
+        let (width, height) = (self.columns as u32, self.pages as u32 * 8);
+        if self.transposed {
+            Size::new(height, width)
+        } else {
+            Size::new(width, height)
+        }
+    }
+
+    pub fn draw<I>(&self, buffer: &mut [u8], pixels: I)
+    where
+        I: IntoIterator<Item = Pixel<BinaryColor>>,
+    {
+        for Pixel(point, color) in pixels {
+            let point = point + Point::new(self.shift.0, self.shift.1);
+            let (x, y) = if self.transposed {
+                (point.y, point.x)
+            } else {
+                (point.x, point.y)
+            };
+
+            if x < 0 || y < 0 || x as usize >= self.columns || y as usize >= self.pages * 8 {
+                continue;
+            }
+
+            let (x, y) = (x as usize, y as usize);
+            let index = y / 8 * self.columns + x;
+            let bit = 1 << (y % 8);
+
+            if color.is_on() {
+                buffer[index] |= bit;
+            } else {
+                buffer[index] &= !bit;
+            }
+        }
+    }
+
+    pub fn clear(&self, buffer: &mut [u8], color: BinaryColor) {
+        let fill = if color.is_on() { 0xff } else { 0x00 };
+        buffer[..self.len()]
+            .iter_mut()
+            .for_each(|byte| *byte = fill);
+    }
+}
@@ -0,0 +1,175 @@
+use {
+    crate::control::PlayerControl,
+    core::sync::atomic::{AtomicI32, Ordering},
+    embedded_hal::digital::v2::InputPin,
+};
+
+/// Both signals changed at once, an edge was missed and the direction is
+/// unknown
+const INVALID: i8 = 2;
+
+/// Count change for a transition, indexed by `previous << 2 | next` with
+/// states `a << 1 | b`. Forward is 00, 01, 11, 10
+#[rustfmt::skip]
+const TRANSITIONS: [i8; 16] = [
+    0, 1, -1, INVALID,
+    -1, 0, INVALID, 1,
+    1, INVALID, 0, -1,
+    INVALID, -1, 1, 0,
+];
+
+/// Counts every edge of two quadrature signals
+#[derive(Debug, Clone, Copy)]
+pub struct Quadrature {
+    state: u8,
+    count: i32,
+    invalid: u32,
+}
+
+impl Quadrature {
+    pub const fn new(a: bool, b: bool) -> Self {
+        Self {
+            state: (a as u8) << 1 | b as u8,
+            count: 0,
+            invalid: 0,
+        }
+    }
+
+    /// Call on every edge or as often as possible, returns false for an
+    /// invalid transition, which isn't counted
+    pub fn update(&mut self, a: bool, b: bool) -> bool {
+        let next = (a as u8) << 1 | b as u8;
+        let change = TRANSITIONS[(self.state << 2 | next) as usize];
+        self.state = next;
+
+        if change == INVALID {
+            self.invalid = self.invalid.wrapping_add(1);
+            false
+        } else {
+            self.count = self.count.wrapping_add(change as i32);
+            true
+        }
+    }
+
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// Number of rejected transitions
+    pub fn invalid(&self) -> u32 {
+        self.invalid
+    }
+}
+
+/// Encoder on two GPIO pins, decoded in software. `poll` has to be called
+/// faster than the edges come, from a pin interrupt or a busy loop
+pub struct SoftEncoder<A, B> {
+    a: A,
+    b: B,
+    decoder: Quadrature,
+    reported: i32,
+}
+
+impl<A, B> SoftEncoder<A, B>
+where
+    A: InputPin,
+    B: InputPin,
+{
+    pub fn new(a: A, b: B) -> Self {
+        let decoder = Quadrature::new(a.is_high().unwrap_or(false), b.is_high().unwrap_or(false));
+        Self {
+            a,
+            b,
+            decoder,
+            reported: 0,
+        }
+    }
+
+    pub fn decoder(&self) -> &Quadrature {
+        &self.decoder
+    }
+
+    /// Both pins, e.g. to clear their interrupt flags
+    pub fn pins(&mut self) -> (&mut A, &mut B) {
+        (&mut self.a, &mut self.b)
+    }
+
+    /// Call from the interrupt of both pins, publishes the count for an
+    /// `InterruptEncoder`
+    pub fn edge(&mut self, count: &EncoderCount) {
+        self.poll();
+        count.store(self.decoder.count());
+    }
+
+    pub fn release(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> PlayerControl for SoftEncoder<A, B>
+where
+    A: InputPin,
+    B: InputPin,
+{
+    fn delta(&mut self) -> i32 {
+        self.poll();
+        let count = self.decoder.count();
+        let delta = count.wrapping_sub(self.reported);
+        self.reported = count;
+        delta
+    }
+
+    fn poll(&mut self) {
+        if let (Ok(a), Ok(b)) = (self.a.is_high(), self.b.is_high()) {
+            self.decoder.update(a, b);
+        }
+    }
+}
+
+/// Count of a `SoftEncoder` that is decoded in an interrupt handler
+pub struct EncoderCount(AtomicI32);
+
+impl EncoderCount {
+    pub const fn new() -> Self {
+        Self(AtomicI32::new(0))
+    }
+
+    pub fn store(&self, count: i32) {
+        self.0.store(count, Ordering::Relaxed);
+    }
+
+    pub fn load(&self) -> i32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for EncoderCount {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Motion of an encoder counted by `SoftEncoder::edge` in a pin interrupt,
+/// no edge is missed while the main loop draws
+pub struct InterruptEncoder<'a> {
+    count: &'a EncoderCount,
+    reported: i32,
+}
+
+impl<'a> InterruptEncoder<'a> {
+    pub fn new(count: &'a EncoderCount) -> Self {
+        Self {
+            count,
+            reported: count.load(),
+        }
+    }
+}
+
+impl<'a> PlayerControl for InterruptEncoder<'a> {
+    fn delta(&mut self) -> i32 {
+        let count = self.count.load();
+        let delta = count.wrapping_sub(self.reported);
+        self.reported = count;
+        delta
+    }
+}
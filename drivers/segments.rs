@@ -0,0 +1,38 @@
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+/// Lit segments of every digit, bits 0 to 6 are segments a to g
+const DIGITS: [u8; 10] = [0x3f, 0x06, 0x5b, 0x4f, 0x66, 0x6d, 0x7d, 0x07, 0x7f, 0x6f];
+
+/// Segment thickness for digits of the given height
+pub fn thickness(height: u32) -> u32 {
+    (height / 7).max(1)
+}
+
+/// Rectangles of the lit segments of the last decimal digit of `value`,
+/// drawn seven segment style into `cell`
+pub fn digit(value: u32, cell: Rectangle) -> impl Iterator<Item = Rectangle> {
+    let Size { width, height } = cell.size;
+    let t = thickness(height);
+    let middle = (height - t) / 2;
+    let lit = DIGITS[(value % 10) as usize];
+
+    let segments = [
+        ((0, 0), (width, t)),
+        ((width - t, 0), (t, middle + t)),
+        ((width - t, middle), (t, height - middle)),
+        ((0, height - t), (width, t)),
+        ((0, middle), (t, height - middle)),
+        ((0, 0), (t, middle + t)),
+        ((0, middle), (width, t)),
+    ];
+
+    (0..segments.len())
+        .filter(move |segment| lit & (1 << segment) != 0)
+        .map(move |segment| {
+            let ((x, y), (w, h)) = segments[segment];
+            Rectangle::new(
+                cell.top_left + Point::new(x as i32, y as i32),
+                Size::new(w, h),
+            )
+        })
+}
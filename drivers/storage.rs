@@ -0,0 +1,52 @@
+/// Non-volatile memory holding one record of `RECORD_SIZE` bytes
+pub trait Storage {
+    type Error;
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), Self::Error>;
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), Self::Error>;
+}
+
+pub const RECORD_SIZE: usize = 64;
+
+/// Largest payload, the record also holds a marker, the payload length and a
+/// checksum
+pub const MAX_PAYLOAD: usize = RECORD_SIZE - 3;
+
+const MARKER: u8 = 0xa5;
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+        .wrapping_neg()
+}
+
+/// Fills `payload` from the stored record, returns false when nothing valid
+/// of this length was stored, e.g. in erased memory or after the layout
+/// changed
+pub fn load<S: Storage>(storage: &mut S, payload: &mut [u8]) -> Result<bool, S::Error> {
+    let mut record = [0; RECORD_SIZE];
+    storage.read(&mut record)?;
+
+    let len = payload.len();
+    let valid = len <= MAX_PAYLOAD
+        && record[0] == MARKER
+        && record[1] as usize == len
+        && record[2 + len] == checksum(&record[..2 + len]);
+
+    if valid {
+        payload.copy_from_slice(&record[2..2 + len]);
+    }
+    Ok(valid)
+}
+
+pub fn save<S: Storage>(storage: &mut S, payload: &[u8]) -> Result<(), S::Error> {
+    let len = payload.len().min(MAX_PAYLOAD);
+    let mut record = [0xff; RECORD_SIZE];
+    record[0] = MARKER;
+    record[1] = len as u8;
+    record[2..2 + len].copy_from_slice(&payload[..len]);
+    record[2 + len] = checksum(&record[..2 + len]);
+
+    storage.write(&record)
+}
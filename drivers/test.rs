@@ -0,0 +1,1169 @@
+macro_rules! add_test {
+    ($($(#[$($mod_macro_args:tt)*])*$name:ident{$($(#[$($fn_macro_args:tt)*])*$subname:ident$(::<$($ty:ty),+$(,)?>)?($($args:expr),*$(,)?)),*$(,)?}),*$(,)?) => {
+        $(
+            $(
+                #[$($mod_macro_args)*]
+            )*
+            mod $name {
+                use super::$name;
+                $(
+                    $(
+                        #[$($fn_macro_args)*]
+                    )*
+                    #[test]
+                    pub fn $subname () {
+                        $name$(::<$($ty,)+>)?($($args,)*);
+                    }
+                )*
+            }
+        )*
+    };
+}
+
+use {
+    crate::{
+        beeper::Beeper,
+        burnin::{BurnIn, BurnInConfig, Idle},
+        control::{
+            Buttons, PlayerControl, Potentiometer, Scaled, Sensitivity, SharedAdc, WithButton,
+        },
+        dma::{DmaDisplay, WriteDma},
+        indexed::{Frame, IndexedDisplay, PixelPanel},
+        input::{Input, Key, KeyConfig},
+        menu::{Entry, Item, Menu},
+        nec::{Keymap, NecCode, NecDecoder, NecEvent, NecReceiver, RemoteCodes, RemotePlayer},
+        nunchuk::{Nunchuk, NunchukState},
+        paged::PagedDisplay,
+        quadrature::{EncoderCount, InterruptEncoder, Quadrature, SoftEncoder},
+        segments,
+        storage::{self, Storage, RECORD_SIZE},
+    },
+    display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand},
+    embedded_graphics::{
+        pixelcolor::{raw::RawU16, BinaryColor, Rgb565},
+        prelude::*,
+        primitives::{Circle, PrimitiveStyle, Rectangle},
+    },
+    embedded_hal::{
+        adc::{Channel, OneShot},
+        blocking::i2c::{Read, Write},
+        digital::v2::{InputPin, OutputPin},
+    },
+    std::{
+        cell::{Cell, RefCell},
+        collections::VecDeque,
+    },
+};
+
+#[derive(Default)]
+struct CountingInterface {
+    commands: usize,
+    data: usize,
+    last_command: Vec<u8>,
+}
+
+impl WriteOnlyDataCommand for CountingInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        if let DataFormat::U8(bytes) = cmd {
+            self.commands += bytes.len();
+            self.last_command = bytes.to_vec();
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        if let DataFormat::U8(bytes) = buf {
+            self.data += bytes.len();
+        }
+        Ok(())
+    }
+}
+
+fn draw_frame(display: &mut PagedDisplay<CountingInterface>, ball: Point, paddles: (i32, i32)) {
+    let on = PrimitiveStyle::with_fill(BinaryColor::On);
+
+    display.clear(BinaryColor::Off).unwrap();
+    Circle::new(ball, 6).into_styled(on).draw(display).unwrap();
+    Rectangle::new(Point::new(0, paddles.0), Size::new(2, 16))
+        .into_styled(on)
+        .draw(display)
+        .unwrap();
+    Rectangle::new(Point::new(126, paddles.1), Size::new(2, 16))
+        .into_styled(on)
+        .draw(display)
+        .unwrap();
+}
+
+fn flush_bytes(ball: (i32, i32), paddles: (i32, i32), max_data: usize, max_commands: usize) {
+    let mut display = PagedDisplay::new(CountingInterface::default(), 128, 64, (0, 0), false);
+
+    draw_frame(&mut display, Point::new(61, 29), (24, 24));
+    assert_eq!(display.flush().unwrap(), 128 * 8);
+
+    draw_frame(&mut display, Point::new(ball.0, ball.1), paddles);
+    let data = display.flush().unwrap();
+    assert_eq!(display.flushed(), data);
+
+    let interface = display.release();
+    let commands = interface.commands - 6 * 8;
+
+    assert_eq!(interface.data, 128 * 8 + data);
+    assert!(data <= max_data, "{} data bytes sent", data);
+    assert!(commands <= max_commands, "{} command bytes sent", commands);
+}
+
+fn transposed(x: i32, y: i32, column: u8, page: u8) {
+    let mut display = PagedDisplay::new(CountingInterface::default(), 128, 64, (2, 1), true);
+    assert_eq!(display.size(), Size::new(64, 128));
+    display.flush().unwrap();
+
+    Pixel(Point::new(x, y), BinaryColor::On)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(display.flush().unwrap(), 1);
+
+    let interface = display.release();
+    assert_eq!(
+        interface.last_command,
+        [0x21, column + 2, column + 2, 0x22, page + 1, page + 1]
+    );
+}
+
+struct RecordingDma {
+    spare: Option<&'static mut [u8]>,
+    sending: Option<&'static mut [u8]>,
+    sent: Vec<Vec<u8>>,
+    commands: Vec<Vec<u8>>,
+}
+
+impl RecordingDma {
+    fn new(size: usize) -> Self {
+        Self {
+            spare: Some(vec![0xaa; size].leak()),
+            sending: None,
+            sent: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+}
+
+impl WriteDma for RecordingDma {
+    fn swap(&mut self, buffer: &'static mut [u8]) -> &'static mut [u8] {
+        let previous = self.sending.take().or_else(|| self.spare.take()).unwrap();
+        self.sent.push(buffer.to_vec());
+        self.sending = Some(buffer);
+        previous
+    }
+
+    fn is_busy(&mut self) -> bool {
+        self.sending.is_some()
+    }
+
+    fn command(&mut self, bytes: &[u8]) {
+        self.commands.push(bytes.to_vec());
+    }
+}
+
+fn double_buffer(x: i32, y: i32, index: usize, bit: u8) {
+    let back = vec![0x55; 128 * 8].leak();
+    let mut display = DmaDisplay::new(RecordingDma::new(128 * 8), back, 128, 64, false);
+    assert!(!display.is_flushing());
+
+    display.clear(BinaryColor::Off).unwrap();
+    Pixel(Point::new(x, y), BinaryColor::On)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(display.flush(), 128 * 8);
+    assert!(display.is_flushing());
+
+    display.clear(BinaryColor::Off).unwrap();
+    display.flush();
+
+    let dma = display.release();
+    assert_eq!(dma.sent.len(), 2);
+    assert_eq!(dma.sent[0][index], bit);
+    assert_eq!(dma.sent[0].iter().filter(|&&byte| byte != 0).count(), 1);
+    assert!(dma.sent[1].iter().all(|&byte| byte == 0));
+}
+
+fn dma_contrast(contrasts: &[u8], sent: &[u8]) {
+    let back = vec![0; 128 * 8].leak();
+    let mut display = DmaDisplay::new(RecordingDma::new(128 * 8), back, 128, 64, false);
+    for &contrast in contrasts {
+        display.set_contrast(contrast);
+    }
+
+    let dma = display.release();
+    let expected: Vec<_> = sent.iter().map(|&contrast| vec![0x81, contrast]).collect();
+    assert_eq!(dma.commands, expected);
+}
+
+fn seven_segments(value: u32, lit: usize, size: (u32, u32)) {
+    let cell = Rectangle::new(Point::new(3, 5), Size::new(size.0, size.1));
+    let segments: Vec<_> = segments::digit(value, cell).collect();
+    assert_eq!(segments.len(), lit);
+
+    for segment in segments {
+        assert!(segment.size.width > 0 && segment.size.height > 0);
+        assert!(cell.contains(segment.top_left));
+        assert!(cell.contains(segment.bottom_right().unwrap()));
+    }
+}
+
+const BURN_IN: BurnInConfig = BurnInConfig {
+    shift_period: 10,
+    max_shift: 1,
+    contrast: 0xcf,
+    dim_contrast: 0x08,
+    dim_after: 20,
+    screensaver_after: 50,
+};
+
+fn burn_in_idle(idle_frames: u32, active_at: Option<u32>, idle: Idle, contrast: u8) {
+    let mut burn_in = BurnIn::new(BURN_IN);
+    for frame in 0..idle_frames {
+        burn_in.next(active_at == Some(frame));
+    }
+
+    assert_eq!(burn_in.idle(), idle);
+    assert_eq!(burn_in.contrast(), contrast);
+}
+
+fn burn_in_shift(frames: u32, shift: (i32, i32)) {
+    let mut burn_in = BurnIn::new(BURN_IN);
+    for _ in 0..frames {
+        burn_in.next(true);
+    }
+
+    assert_eq!(burn_in.shift(), shift);
+}
+
+fn screensaver(frames: u32, range: (u32, u32), position: (i32, i32)) {
+    let mut burn_in = BurnIn::new(BURN_IN);
+    for _ in 0..frames {
+        burn_in.next(false);
+    }
+
+    assert_eq!(burn_in.screensaver_position(range), position);
+}
+
+fn shifted_pixel(shift: (i32, i32), data: usize) {
+    let mut display = PagedDisplay::new(CountingInterface::default(), 128, 64, (0, 0), false);
+    display.set_shift(shift);
+    display.flush().unwrap();
+
+    Pixel(Point::new(0, 0), BinaryColor::On)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(display.flush().unwrap(), data);
+
+    let interface = display.release();
+    if data > 0 {
+        let column = shift.0 as u8;
+        let page = (shift.1 / 8) as u8;
+        assert_eq!(
+            interface.last_command,
+            [0x21, column, column, 0x22, page, page]
+        );
+    }
+}
+
+fn contrast(values: &[u8], commands: usize) {
+    let mut display = PagedDisplay::new(CountingInterface::default(), 128, 64, (0, 0), false);
+    for &value in values {
+        display.set_contrast(value).unwrap();
+    }
+
+    let interface = display.release();
+    assert_eq!(interface.commands, 2 * commands);
+}
+
+/// Rectangles set on a colour panel and their pixels
+#[derive(Default)]
+struct RecordingPanel {
+    rects: Vec<((u16, u16), (u16, u16))>,
+    pixels: Vec<u16>,
+}
+
+impl PixelPanel for RecordingPanel {
+    type Error = ();
+
+    fn set_pixels<P>(&mut self, start: (u16, u16), end: (u16, u16), colors: P) -> Result<(), ()>
+    where
+        P: IntoIterator<Item = u16>,
+    {
+        self.rects.push((start, end));
+        self.pixels.extend(colors);
+        Ok(())
+    }
+}
+
+fn draw_colored(display: &mut IndexedDisplay<RecordingPanel>, ball: Point, paddle: i32) {
+    display.clear(Rgb565::BLACK).unwrap();
+    Circle::new(ball, 6)
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::YELLOW))
+        .draw(display)
+        .unwrap();
+    Rectangle::new(Point::new(4, paddle), Size::new(3, 20))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(display)
+        .unwrap();
+}
+
+fn indexed_flush(ball: (i32, i32), paddle: i32, pixels: usize, rects: usize) {
+    let frame = Box::leak(Box::new(Frame::new()));
+    let mut display = IndexedDisplay::new(RecordingPanel::default(), frame, 160, 128);
+
+    draw_colored(&mut display, Point::new(77, 61), 50);
+    assert_eq!(display.flush().unwrap(), 160 * 128);
+
+    draw_colored(&mut display, Point::new(ball.0, ball.1), paddle);
+    assert_eq!(display.flush().unwrap(), pixels);
+    assert_eq!(display.flushed(), pixels);
+
+    let panel = display.release();
+    assert_eq!(panel.pixels.len(), 160 * 128 + pixels);
+    assert_eq!(panel.rects.len() - 16, rects);
+}
+
+fn indexed_colors(size: (u16, u16), point: (i32, i32), color: Rgb565, sent: (u16, u16)) {
+    let frame = Box::leak(Box::new(Frame::new()));
+    let mut display = IndexedDisplay::new(RecordingPanel::default(), frame, size.0, size.1);
+    assert_eq!(display.size(), Size::new(size.0 as u32, size.1 as u32));
+    display.clear(Rgb565::BLUE).unwrap();
+    display.flush().unwrap();
+
+    Pixel(Point::new(point.0, point.1), color)
+        .draw(&mut display)
+        .unwrap();
+    assert_eq!(display.flush().unwrap(), 64);
+
+    let panel = display.release();
+    assert_eq!(panel.rects.last(), Some(&(sent, (sent.0 + 7, sent.1 + 7))));
+
+    let tile = &panel.pixels[panel.pixels.len() - 64..];
+    let offset = (point.1 as usize % 8) * 8 + point.0 as usize % 8;
+    assert_eq!(tile[offset], RawU16::from(color).into_inner());
+    let blue = RawU16::from(Rgb565::BLUE).into_inner();
+    assert_eq!(tile.iter().filter(|&&pixel| pixel == blue).count(), 63);
+}
+
+const KEY: KeyConfig = KeyConfig {
+    debounce: 2,
+    long_press: 10,
+    double_press: 5,
+};
+
+/// Feeds raw key levels, one per frame, and collects the events with the
+/// frame they happened in
+fn key_events(levels: &str, events: &[(usize, crate::input::KeyEvent)]) {
+    let mut key = Key::new(KEY);
+    let happened: Vec<_> = levels
+        .chars()
+        .enumerate()
+        .filter_map(|(frame, level)| key.update(level == '#').map(|event| (frame, event)))
+        .collect();
+
+    assert_eq!(happened, events);
+}
+
+struct SharedPin<'a>(&'a Cell<bool>);
+
+impl InputPin for SharedPin<'_> {
+    type Error = ();
+
+    fn is_high(&self) -> Result<bool, ()> {
+        Ok(self.0.get())
+    }
+
+    fn is_low(&self) -> Result<bool, ()> {
+        Ok(!self.0.get())
+    }
+}
+
+struct SharedControl<'a>(&'a Cell<i32>);
+
+impl PlayerControl for SharedControl<'_> {
+    fn delta(&mut self) -> i32 {
+        self.0.take()
+    }
+}
+
+fn input_motion(deltas: &[(i32, i32)], taken_at: Option<usize>, motion: (i32, i32)) {
+    let (delta1, delta2, high) = (Cell::new(0), Cell::new(0), Cell::new(true));
+    let mut input = Input::new(
+        SharedControl(&delta1),
+        SharedControl(&delta2),
+        SharedPin(&high),
+        KEY,
+    );
+
+    for (frame, &(first, second)) in deltas.iter().enumerate() {
+        delta1.set(first);
+        delta2.set(second);
+        let active = input.sample().unwrap();
+        assert_eq!(active, first != 0 || second != 0);
+
+        if taken_at == Some(frame) {
+            input.take_motion();
+        }
+    }
+
+    assert_eq!(input.motion(), motion);
+    assert!(!input.pressed());
+}
+
+/// Levels of the shared key and the switches of both players, `#` is held,
+/// collects the frames with a press of the key, player 1 or player 2
+fn player_buttons(key: &str, switch1: &str, switch2: &str, pressed: &[(usize, bool, bool, bool)]) {
+    let (delta1, delta2) = (Cell::new(0), Cell::new(0));
+    let (high, high1, high2) = (Cell::new(true), Cell::new(true), Cell::new(true));
+    let mut input = Input::new(
+        WithButton::new(SharedControl(&delta1), SharedPin(&high1)),
+        WithButton::new(SharedControl(&delta2), SharedPin(&high2)),
+        SharedPin(&high),
+        KEY,
+    );
+
+    let levels = key.chars().zip(switch1.chars()).zip(switch2.chars());
+    let mut happened = Vec::new();
+    for (frame, ((key, switch1), switch2)) in levels.enumerate() {
+        high.set(key != '#');
+        high1.set(switch1 != '#');
+        high2.set(switch2 != '#');
+        let active = input.sample().unwrap();
+        assert_eq!(active, key == '#' || switch1 == '#' || switch2 == '#');
+
+        let (pressed1, pressed2) = input.player_pressed();
+        assert_eq!(input.pressed(), input.key_pressed() || pressed1 || pressed2);
+        if input.pressed() {
+            happened.push((frame, input.key_pressed(), pressed1, pressed2));
+        }
+    }
+
+    assert_eq!(happened, pressed);
+}
+
+const MENU_LEVELS: &[&str] = &["EASY", "NORMAL", "HARD"];
+
+fn test_menu() -> Menu<5> {
+    Menu::new([
+        Entry {
+            label: "PLAY",
+            item: Item::Action,
+        },
+        Entry {
+            label: "LEVEL",
+            item: Item::List {
+                options: MENU_LEVELS,
+                index: 1,
+            },
+        },
+        Entry {
+            label: "SCORE",
+            item: Item::Spinner {
+                value: 11,
+                min: 1,
+                max: 21,
+                step: 2,
+            },
+        },
+        Entry {
+            label: "SOUND",
+            item: Item::Toggle(true),
+        },
+        Entry {
+            label: "NAME",
+            item: Item::Text(*b"AB    "),
+        },
+    ])
+}
+
+/// Feeds motion and presses, one per frame, and collects the events with
+/// the frame they happened in
+fn menu(
+    frames: &[(i32, bool)],
+    events: &[(usize, crate::menu::MenuEvent)],
+    selected: usize,
+    mode: crate::menu::Mode,
+    item: crate::menu::Item,
+) {
+    let mut menu = test_menu();
+    let happened: Vec<_> = frames
+        .iter()
+        .enumerate()
+        .filter_map(|(frame, &(motion, pressed))| {
+            menu.update(motion, pressed).map(|event| (frame, event))
+        })
+        .collect();
+
+    assert_eq!(happened, events);
+    assert_eq!(menu.selected(), selected);
+    assert_eq!(menu.mode(), mode);
+    assert_eq!(menu.item(selected), item);
+}
+
+fn item_text(item: crate::menu::Item, text: &str) {
+    let mut buffer = [0; 11];
+    assert_eq!(item.text(&mut buffer), text);
+}
+
+struct SharedOutput<'a>(&'a Cell<bool>);
+
+impl OutputPin for SharedOutput<'_> {
+    type Error = ();
+
+    fn set_high(&mut self) -> Result<(), ()> {
+        self.0.set(true);
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), ()> {
+        self.0.set(false);
+        Ok(())
+    }
+}
+
+/// Starts beeps of some frames at the given frames and collects the level
+/// of the pin, `#` is high
+fn beeper(enabled: bool, beeps: &[(usize, u32)], levels: &str) {
+    let high = Cell::new(false);
+    let mut beeper = Beeper::new(SharedOutput(&high));
+    beeper.set_enabled(enabled);
+
+    let sounded: String = (0..levels.len())
+        .map(|frame| {
+            for &(_, frames) in beeps.iter().filter(|&&(at, _)| at == frame) {
+                beeper.beep(frames);
+            }
+            beeper.update().unwrap();
+            if high.get() {
+                '#'
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    assert_eq!(sounded, levels);
+}
+
+struct MockAdc<'a>(&'a Cell<u16>);
+
+struct MockChannel;
+
+impl Channel<MockAdc<'_>> for MockChannel {
+    type ID = u8;
+
+    fn channel() -> u8 {
+        0
+    }
+}
+
+impl<'a> OneShot<MockAdc<'a>, u16, MockChannel> for MockAdc<'a> {
+    type Error = ();
+
+    fn read(&mut self, _pin: &mut MockChannel) -> nb::Result<u16, ()> {
+        Ok(self.0.get())
+    }
+}
+
+/// Samples of a 10 bit ADC over 64 counts, 16 ADC steps per count
+fn potentiometer(samples: &[u16], steps: &[i32]) {
+    let sample = Cell::new(0);
+    let adc = RefCell::new(MockAdc(&sample));
+    let mut control = Potentiometer::new(SharedAdc(&adc), MockChannel, 1023, 64);
+
+    let moved: Vec<_> = samples
+        .iter()
+        .map(|&value| {
+            sample.set(value);
+            control.delta()
+        })
+        .collect();
+
+    assert_eq!(moved, steps);
+}
+
+fn buttons(pressed: &str, steps: &[i32]) {
+    let (up, down) = (Cell::new(true), Cell::new(true));
+    let mut control = Buttons::new(SharedPin(&up), SharedPin(&down));
+
+    let moved: Vec<_> = pressed
+        .chars()
+        .map(|button| {
+            up.set(!matches!(button, 'u' | 'b'));
+            down.set(!matches!(button, 'd' | 'b'));
+            control.delta()
+        })
+        .collect();
+
+    assert_eq!(moved, steps);
+}
+
+fn buttons_held(frames: usize, steps: i32) {
+    let (up, down) = (Cell::new(false), Cell::new(true));
+    let mut control = Buttons::new(SharedPin(&up), SharedPin(&down));
+
+    let moved: i32 = (0..frames).map(|_| control.delta()).sum();
+    assert_eq!(moved, steps);
+}
+
+/// States as `a << 1 | b`, one digit per edge
+fn quadrature(states: &str, count: i32, invalid: u32) {
+    let mut decoder = Quadrature::new(false, false);
+    for state in states.chars() {
+        let state = state.to_digit(4).unwrap();
+        decoder.update(state & 2 != 0, state & 1 != 0);
+    }
+
+    assert_eq!(decoder.count(), count);
+    assert_eq!(decoder.invalid(), invalid);
+}
+
+fn soft_encoder(polled: &[(bool, bool)], frames: &[usize], deltas: &[i32]) {
+    let (a, b) = (Cell::new(false), Cell::new(false));
+    let mut encoder = SoftEncoder::new(SharedPin(&a), SharedPin(&b));
+
+    let mut reported = Vec::new();
+    for (index, &(level_a, level_b)) in polled.iter().enumerate() {
+        a.set(level_a);
+        b.set(level_b);
+        encoder.poll();
+        if frames.contains(&index) {
+            reported.push(encoder.delta());
+        }
+    }
+
+    assert_eq!(reported, deltas);
+}
+
+fn interrupt_encoder(edges: &[(bool, bool)], frames: &[usize], deltas: &[i32]) {
+    let (a, b) = (Cell::new(false), Cell::new(false));
+    let mut encoder = SoftEncoder::new(SharedPin(&a), SharedPin(&b));
+    let count = EncoderCount::new();
+    let mut player = InterruptEncoder::new(&count);
+
+    let mut reported = Vec::new();
+    for (index, &(level_a, level_b)) in edges.iter().enumerate() {
+        a.set(level_a);
+        b.set(level_b);
+        encoder.edge(&count);
+        if frames.contains(&index) {
+            reported.push(player.delta());
+        }
+    }
+
+    assert_eq!(reported, deltas);
+}
+
+/// Register dumps read from a Nunchuk
+const NUNCHUK_IDLE: [u8; 6] = [0x80, 0x7f, 0x7c, 0x83, 0xb2, 0x4b];
+const NUNCHUK_UP: [u8; 6] = [0x81, 0xe0, 0x7d, 0x82, 0xb1, 0x5f];
+const NUNCHUK_DOWN: [u8; 6] = [0x7f, 0x1e, 0x7b, 0x84, 0xb4, 0x37];
+const NUNCHUK_Z: [u8; 6] = [0x80, 0x80, 0x7c, 0x83, 0xb2, 0x4a];
+const NUNCHUK_C: [u8; 6] = [0x80, 0x7f, 0x7c, 0x83, 0xb2, 0x49];
+const NUNCHUK_UNPLUGGED: [u8; 6] = [0xff; 6];
+
+/// Serves register dumps, `None` fails the read
+#[derive(Default)]
+struct MockI2c {
+    dumps: VecDeque<Option<[u8; 6]>>,
+    writes: Vec<Vec<u8>>,
+}
+
+impl Write for MockI2c {
+    type Error = ();
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        assert_eq!(address, 0x52);
+        self.writes.push(bytes.to_vec());
+        Ok(())
+    }
+}
+
+impl Read for MockI2c {
+    type Error = ();
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), ()> {
+        assert_eq!(address, 0x52);
+        let dump = self.dumps.pop_front().unwrap().ok_or(())?;
+        buffer.copy_from_slice(&dump);
+        Ok(())
+    }
+}
+
+fn nunchuk_state(
+    dump: [u8; 6],
+    joystick: (u8, u8),
+    acceleration: (u16, u16, u16),
+    c: bool,
+    z: bool,
+) {
+    assert_eq!(
+        NunchukState::from_bytes(dump),
+        NunchukState {
+            joystick,
+            acceleration,
+            c,
+            z,
+        }
+    );
+}
+
+/// Every initialization takes a frame, the other frames read one dump each
+fn nunchuk_frames(dumps: &[Option<[u8; 6]>], deltas: &[i32], inits: usize, keys: (bool, bool)) {
+    let i2c = MockI2c {
+        dumps: dumps.iter().cloned().collect(),
+        writes: Vec::new(),
+    };
+    let mut nunchuk = Nunchuk::new(i2c);
+
+    let moved: Vec<_> = (0..dumps.len() + inits).map(|_| nunchuk.delta()).collect();
+    assert_eq!(moved, deltas);
+
+    let keys_down = nunchuk.keys();
+    assert_eq!((keys_down.action, keys_down.pause), keys);
+
+    let i2c = nunchuk.release();
+    let started = i2c
+        .writes
+        .iter()
+        .filter(|bytes| bytes[..] == [0xf0, 0x55])
+        .count();
+    assert_eq!(started, inits);
+    assert_eq!(
+        i2c.writes[..3],
+        [vec![0xf0, 0x55], vec![0xfb, 0x00], vec![0x00]]
+    );
+}
+
+/// Lengths measured from a 21 button remote behind a receiver module, which
+/// stretches marks a little
+const NEC_LEADER: [(bool, u32); 2] = [(true, 9040), (false, 4430)];
+const NEC_ZERO: [(bool, u32); 2] = [(true, 620), (false, 500)];
+const NEC_ONE: [(bool, u32); 2] = [(true, 620), (false, 1630)];
+const NEC_STOP: (bool, u32) = (true, 620);
+const NEC_REPEAT: [(bool, u32); 3] = [(true, 9040), (false, 2200), (true, 620)];
+
+/// Marks and spaces of a frame sending `bytes`, after a space of `gap`
+/// microseconds
+fn nec_frame(gap: u32, bytes: [u8; 4]) -> Vec<(bool, u32)> {
+    let mut pulses = vec![(false, gap)];
+    pulses.extend_from_slice(&NEC_LEADER);
+    for bit in 0..32 {
+        if u32::from_le_bytes(bytes) & 1 << bit != 0 {
+            pulses.extend_from_slice(&NEC_ONE);
+        } else {
+            pulses.extend_from_slice(&NEC_ZERO);
+        }
+    }
+    pulses.push(NEC_STOP);
+    pulses
+}
+
+fn nec_repeat(gap: u32) -> Vec<(bool, u32)> {
+    let mut pulses = vec![(false, gap)];
+    pulses.extend_from_slice(&NEC_REPEAT);
+    pulses
+}
+
+fn nec_decode(parts: &[Vec<(bool, u32)>], events: &[crate::nec::NecEvent]) {
+    let mut decoder = NecDecoder::new();
+    let decoded: Vec<_> = parts
+        .iter()
+        .flatten()
+        .filter_map(|&(mark, duration)| decoder.pulse(mark, duration))
+        .collect();
+
+    assert_eq!(decoded, events);
+}
+
+/// Edges are timed with a 72 MHz counter that wraps during the frame
+fn nec_receiver(bytes: [u8; 4], code: crate::nec::NecCode) {
+    let level = Cell::new(true);
+    let mut receiver = NecReceiver::new(SharedPin(&level), 72);
+    let mut now = u32::MAX - 2_000_000;
+    let mut decoded = Vec::new();
+
+    for (mark, duration) in nec_frame(50_000, bytes) {
+        now = now.wrapping_add(duration * 72);
+        // high after a mark
+        level.set(mark);
+        decoded.extend(receiver.edge(now));
+    }
+
+    assert_eq!(decoded, [NecEvent::Code(code)]);
+}
+
+/// One entry per frame, `Some` pushes an event before the frame
+fn remote_player(events: &[Option<crate::nec::NecEvent>], deltas: &[i32], keys: (bool, bool)) {
+    let codes = RemoteCodes::new();
+    codes.push(NecEvent::Code(NecCode {
+        address: 0,
+        command: 0x47,
+    }));
+    // the code pushed before is stale
+    let mut player = RemotePlayer::new(&codes, Keymap::LEFT);
+
+    let moved: Vec<_> = events
+        .iter()
+        .map(|&event| {
+            if let Some(event) = event {
+                codes.push(event);
+            }
+            player.delta()
+        })
+        .collect();
+    assert_eq!(moved, deltas);
+
+    let keys_down = player.keys();
+    assert_eq!((keys_down.action, keys_down.pause), keys);
+}
+
+/// Players on both halves of one remote, one list of events per frame
+fn remote_players(frames: &[&[crate::nec::NecEvent]], deltas: &[(i32, i32)]) {
+    let codes = RemoteCodes::new();
+    let mut player1 = RemotePlayer::new(&codes, Keymap::LEFT);
+    let mut player2 = RemotePlayer::new(&codes, Keymap::RIGHT);
+
+    let moved: Vec<_> = frames
+        .iter()
+        .map(|&events| {
+            for &event in events {
+                codes.push(event);
+            }
+            (player1.delta(), player2.delta())
+        })
+        .collect();
+    assert_eq!(moved, deltas);
+}
+
+fn sensitivity(counts: &[i32], sensitivity: crate::control::Sensitivity, steps: &[i32]) {
+    let delta = Cell::new(0);
+    let mut control = Scaled::new(SharedControl(&delta), sensitivity);
+    let moved: Vec<_> = counts
+        .iter()
+        .map(|&count| {
+            delta.set(count);
+            control.delta()
+        })
+        .collect();
+
+    assert_eq!(moved, steps);
+}
+
+fn calibrated(counts: i32, steps: u32, scale: u16, invert: bool) {
+    let sensitivity = Sensitivity {
+        acceleration: 20,
+        dead_zone: 1,
+        ..Sensitivity::RAW
+    }
+    .calibrated(counts, steps);
+
+    assert_eq!(sensitivity.scale, scale);
+    assert_eq!(sensitivity.invert, invert);
+    assert_eq!((sensitivity.acceleration, sensitivity.dead_zone), (20, 1));
+    assert_eq!(Sensitivity::from_bytes(sensitivity.to_bytes()), sensitivity);
+}
+
+struct MemoryStorage([u8; RECORD_SIZE]);
+
+impl Storage for MemoryStorage {
+    type Error = ();
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), ()> {
+        *record = self.0;
+        Ok(())
+    }
+
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), ()> {
+        self.0 = *record;
+        Ok(())
+    }
+}
+
+fn stored(saved: Option<&[u8]>, corrupt: Option<usize>, len: usize, valid: bool) {
+    let mut memory = MemoryStorage([0xff; RECORD_SIZE]);
+    if let Some(payload) = saved {
+        storage::save(&mut memory, payload).unwrap();
+    }
+    if let Some(index) = corrupt {
+        memory.0[index] ^= 0x10;
+    }
+
+    let mut payload = vec![0; len];
+    assert_eq!(storage::load(&mut memory, &mut payload).unwrap(), valid);
+    if valid {
+        assert_eq!(Some(&payload[..]), saved);
+    }
+}
+
+add_test! {
+    flush_bytes {
+        still((61, 29), (24, 24), 0, 0),
+        ball_step((63, 30), (24, 24), 16, 6 * 2),
+        paddle_step((61, 29), (26, 24), 4, 6 * 2),
+        both_paddles((61, 29), (26, 20), 8, 6 * 4),
+        everything((64, 31), (27, 21), 26, 6 * 6),
+    },
+    transposed {
+        origin(0, 0, 0, 0),
+        corner(63, 127, 127, 7),
+        middle(20, 50, 50, 2),
+    },
+    double_buffer {
+        origin(0, 0, 0, 0x01),
+        last(127, 63, 128 * 8 - 1, 0x80),
+        middle(40, 21, 2 * 128 + 40, 0x20),
+    },
+    dma_contrast {
+        once(&[0xcf], &[0xcf]),
+        unchanged(&[0xcf, 0xcf], &[0xcf]),
+        dimmed(&[0xcf, 0xcf, 0x08, 0xcf], &[0xcf, 0x08, 0xcf]),
+    },
+    burn_in_idle {
+        active(10, None, crate::burnin::Idle::Active, 0xcf),
+        dimmed(20, None, crate::burnin::Idle::Dimmed, 0x08),
+        screensaver(50, None, crate::burnin::Idle::Screensaver, 0x08),
+        woken(50, Some(40), crate::burnin::Idle::Active, 0xcf),
+    },
+    burn_in_shift {
+        start(0, (-1, -1)),
+        still(9, (-1, -1)),
+        moved(10, (0, -1)),
+        next_row(30, (-1, 0)),
+        wrapped(90, (-1, -1)),
+    },
+    screensaver {
+        start(0, (10, 5), (0, 0)),
+        moving(6, (10, 5), (6, 3)),
+        back(14, (10, 5), (6, 3)),
+    },
+    shifted_pixel {
+        none((0, 0), 1),
+        right((3, 0), 1),
+        down((0, 9), 1),
+        outside((-1, 0), 0),
+    },
+    indexed_flush {
+        still((77, 61), 50, 0, 0),
+        ball_step((79, 62), 50, 4 * 64, 2),
+        paddle_step((77, 61), 54, 3 * 64, 3),
+        both((80, 64), 46, 7 * 64, 5),
+    },
+    indexed_colors {
+        origin((160, 128), (0, 0), embedded_graphics::pixelcolor::Rgb565::new(31, 0, 0), (0, 0)),
+        corner((160, 128), (159, 127), embedded_graphics::pixelcolor::Rgb565::new(0, 63, 0), (152, 120)),
+        portrait((128, 160), (100, 150), embedded_graphics::pixelcolor::Rgb565::new(31, 63, 31), (96, 144)),
+    },
+    contrast {
+        once(&[0x10], 1),
+        unchanged(&[0x10, 0x10], 1),
+        changed(&[0x10, 0x20, 0x10], 3),
+    },
+    seven_segments {
+        zero(0, 6, (6, 12)),
+        one(1, 2, (6, 12)),
+        seven(7, 3, (4, 7)),
+        eight(8, 7, (10, 20)),
+        last_digit(14, 4, (8, 15)),
+    },
+    key_events {
+        nothing("_____", &[]),
+        press("_##", &[(2, crate::input::KeyEvent::Press)]),
+        bounce("_#_#_##_#_", &[(6, crate::input::KeyEvent::Press)]),
+        release("_###__", &[
+            (2, crate::input::KeyEvent::Press),
+            (5, crate::input::KeyEvent::Release),
+        ]),
+        long_press("_############__", &[
+            (2, crate::input::KeyEvent::Press),
+            (12, crate::input::KeyEvent::LongPress),
+            (14, crate::input::KeyEvent::Release),
+        ]),
+        double_press("_##__##__", &[
+            (2, crate::input::KeyEvent::Press),
+            (4, crate::input::KeyEvent::Release),
+            (6, crate::input::KeyEvent::DoublePress),
+            (8, crate::input::KeyEvent::Release),
+        ]),
+        too_slow("_##_______##_", &[
+            (2, crate::input::KeyEvent::Press),
+            (4, crate::input::KeyEvent::Release),
+            (11, crate::input::KeyEvent::Press),
+        ]),
+        no_triple("_##__##__##_", &[
+            (2, crate::input::KeyEvent::Press),
+            (4, crate::input::KeyEvent::Release),
+            (6, crate::input::KeyEvent::DoublePress),
+            (8, crate::input::KeyEvent::Release),
+            (10, crate::input::KeyEvent::Press),
+        ]),
+    },
+    input_motion {
+        idle(&[(0, 0), (0, 0)], None, (0, 0)),
+        added(&[(1, -2), (3, 0), (0, -1)], None, (4, -3)),
+        taken(&[(1, -2), (3, 0), (0, -1)], Some(1), (0, -1)),
+    },
+    player_buttons {
+        nothing("____", "____", "____", &[]),
+        key("_##_", "____", "____", &[(2, true, false, false)]),
+        player1("____", "_###", "____", &[(2, false, true, false)]),
+        player2("____", "____", "##__", &[(1, false, false, true)]),
+        both("____", "_##_", "__##", &[(2, false, true, false), (3, false, false, true)]),
+        bounce("______", "#_#_##", "______", &[(5, false, true, false)]),
+    },
+    menu {
+        play(&[(0, true)], &[(0, crate::menu::MenuEvent::Activated(0))], 0, crate::menu::Mode::Browsing, crate::menu::Item::Action),
+        browse(&[(-4, false)], &[], 1, crate::menu::Mode::Browsing, crate::menu::Item::List { options: super::MENU_LEVELS, index: 1 }),
+        half_steps(&[(-2, false), (-3, false)], &[], 1, crate::menu::Mode::Browsing, crate::menu::Item::List { options: super::MENU_LEVELS, index: 1 }),
+        first(&[(8, false)], &[], 0, crate::menu::Mode::Browsing, crate::menu::Item::Action),
+        last(&[(-40, false)], &[], 4, crate::menu::Mode::Browsing, crate::menu::Item::Text(*b"AB    ")),
+        list(&[(-4, false), (0, true), (-4, false), (0, true)], &[(3, crate::menu::MenuEvent::Changed(1))], 1, crate::menu::Mode::Browsing, crate::menu::Item::List { options: super::MENU_LEVELS, index: 0 }),
+        list_end(&[(-4, false), (0, true), (12, false), (0, true)], &[(3, crate::menu::MenuEvent::Changed(1))], 1, crate::menu::Mode::Browsing, crate::menu::Item::List { options: super::MENU_LEVELS, index: 2 }),
+        editing(&[(-8, false), (0, true), (8, false)], &[], 2, crate::menu::Mode::Editing, crate::menu::Item::Spinner { value: 15, min: 1, max: 21, step: 2 }),
+        spinner_max(&[(-8, false), (0, true), (40, false), (0, true)], &[(3, crate::menu::MenuEvent::Changed(2))], 2, crate::menu::Mode::Browsing, crate::menu::Item::Spinner { value: 21, min: 1, max: 21, step: 2 }),
+        toggle(&[(-12, false), (0, true)], &[(1, crate::menu::MenuEvent::Changed(3))], 3, crate::menu::Mode::Browsing, crate::menu::Item::Toggle(false)),
+        text(
+            &[(-16, false), (0, true), (4, false), (0, true), (-4, false), (0, true), (0, true), (0, true), (0, true), (0, true)],
+            &[(9, crate::menu::MenuEvent::Changed(4))],
+            4,
+            crate::menu::Mode::Browsing,
+            crate::menu::Item::Text(*b"BA    "),
+        ),
+        text_wrap(&[(-16, false), (0, true), (-8, false)], &[], 4, crate::menu::Mode::EditingText(0), crate::menu::Item::Text(*b"-B    ")),
+    },
+    item_text {
+        action(crate::menu::Item::Action, ""),
+        list(crate::menu::Item::List { options: super::MENU_LEVELS, index: 2 }, "HARD"),
+        spinner(crate::menu::Item::Spinner { value: 150, min: 0, max: 200, step: 10 }, "150"),
+        negative(crate::menu::Item::Spinner { value: -5, min: -9, max: 9, step: 1 }, "-5"),
+        on(crate::menu::Item::Toggle(true), "ON"),
+        off(crate::menu::Item::Toggle(false), "OFF"),
+        text(crate::menu::Item::Text(*b"ANNA  "), "ANNA  "),
+    },
+    beeper {
+        silent(true, &[], "____"),
+        beep(true, &[(1, 2)], "_##__"),
+        longer(true, &[(0, 3), (1, 1)], "###_"),
+        extended(true, &[(0, 1), (1, 3)], "####_"),
+        disabled(false, &[(0, 3)], "____"),
+    },
+    sensitivity {
+        raw(&[1, -3, 0], crate::control::Sensitivity::RAW, &[1, -3, 0]),
+        half(&[1, 1, 1, 1, 3], crate::control::Sensitivity {
+            scale: 50,
+            ..crate::control::Sensitivity::RAW
+        }, &[0, 1, 0, 1, 1]),
+        turned_back(&[1, -1, -1], crate::control::Sensitivity {
+            scale: 50,
+            ..crate::control::Sensitivity::RAW
+        }, &[0, 0, -1]),
+        inverted(&[2, -1], crate::control::Sensitivity {
+            invert: true,
+            ..crate::control::Sensitivity::RAW
+        }, &[-2, 1]),
+        dead_zone(&[1, -1, 2, -3], crate::control::Sensitivity {
+            dead_zone: 1,
+            ..crate::control::Sensitivity::RAW
+        }, &[0, 0, 2, -3]),
+        acceleration(&[1, 4, 10], crate::control::Sensitivity {
+            acceleration: 25,
+            ..crate::control::Sensitivity::RAW
+        }, &[1, 8, 35]),
+    },
+    calibrated {
+        slow(32, 64, 200, false),
+        fast(-256, 64, 25, true),
+        tiny(1000, 1, 1, false),
+    },
+    stored {
+        saved(Some(&[1, 2, 3]), None, 3, true),
+        empty(Some(&[]), None, 0, true),
+        erased(None, None, 3, false),
+        other_layout(Some(&[1, 2, 3]), None, 4, false),
+        corrupt(Some(&[1, 2, 3]), Some(3), 3, false),
+    },
+    potentiometer {
+        still(&[512, 512, 512], &[0, 0, 0]),
+        first_sample(&[1000, 1000], &[0, 0]),
+        filtered(&[0, 160, 160, 160, 160], &[0, 2, 2, 1, 1]),
+        jitter(&[512, 516, 508, 517, 507], &[0, 0, 0, 0, 0]),
+        full_range(&[0, 1023, 1023, 1023, 1023, 1023, 1023, 1023, 1023, 1023, 1023, 1023], &[
+            0, 15, 12, 9, 7, 5, 4, 3, 2, 2, 1, 0,
+        ]),
+    },
+    buttons {
+        idle("___", &[0, 0, 0]),
+        tap_up("u__", &[1, 0, 0]),
+        tap_down("_d_", &[0, -1, 0]),
+        both("bb", &[0, 0]),
+        reversed("uud", &[1, 1, -1]),
+    },
+    buttons_held {
+        one(1, 1),
+        ten(10, 12),
+        long(100, 308),
+    },
+    quadrature {
+        still("0000", 0, 0),
+        forward("1320", 4, 0),
+        backward("2310", -4, 0),
+        wiggle("1010", 0, 0),
+        two_turns("13201320", 8, 0),
+        missed_edge("1302", 1, 1),
+        noise("03030", 0, 4),
+    },
+    soft_encoder {
+        forward(&[(false, true), (true, true), (true, false), (false, false)], &[1, 3], &[2, 2]),
+        backward(&[(true, false), (true, true)], &[1], &[-2]),
+        unpolled_frame(&[(false, true), (false, true)], &[0, 1], &[1, 0]),
+    },
+    interrupt_encoder {
+        forward(&[(false, true), (true, true), (true, false), (false, false)], &[1, 3], &[2, 2]),
+        backward(&[(true, false), (true, true)], &[1], &[-2]),
+        between_frames(&[(false, true), (true, true), (true, false)], &[2], &[3]),
+        idle(&[(false, false), (false, false)], &[0, 1], &[0, 0]),
+    },
+    nunchuk_state {
+        idle(super::NUNCHUK_IDLE, (0x80, 0x7f), (0x1f2, 0x20c, 0x2c9), false, false),
+        up(super::NUNCHUK_UP, (0x81, 0xe0), (0x1f7, 0x209, 0x2c5), false, false),
+        z(super::NUNCHUK_Z, (0x80, 0x80), (0x1f2, 0x20c, 0x2c9), false, true),
+        c(super::NUNCHUK_C, (0x80, 0x7f), (0x1f2, 0x20c, 0x2c9), true, false),
+    },
+    nunchuk_frames {
+        idle(&[Some(super::NUNCHUK_IDLE), Some(super::NUNCHUK_IDLE)], &[0, 0, 0], 1, (false, false)),
+        up(&[Some(super::NUNCHUK_IDLE), Some(super::NUNCHUK_UP), Some(super::NUNCHUK_UP)], &[0, 0, 2, 3], 1, (false, false)),
+        down(&[Some(super::NUNCHUK_IDLE), Some(super::NUNCHUK_DOWN), Some(super::NUNCHUK_DOWN)], &[0, 0, -2, -3], 1, (false, false)),
+        serve(&[Some(super::NUNCHUK_IDLE), Some(super::NUNCHUK_Z)], &[0, 0, 0], 1, (true, false)),
+        pause(&[Some(super::NUNCHUK_IDLE), Some(super::NUNCHUK_C)], &[0, 0, 0], 1, (false, true)),
+        read_error(&[Some(super::NUNCHUK_IDLE), None, Some(super::NUNCHUK_UP)], &[0, 0, 0, 0, 2], 2, (false, false)),
+        unplugged(&[Some(super::NUNCHUK_UNPLUGGED), Some(super::NUNCHUK_UP)], &[0, 0, 0, 2], 2, (false, false)),
+    },
+    nec_decode {
+        frame(&[super::nec_frame(50_000, [0x00, 0xff, 0x45, 0xba])], &[super::NecEvent::Code(super::NecCode { address: 0x00, command: 0x45 })]),
+        repeats(&[super::nec_frame(50_000, [0x00, 0xff, 0x45, 0xba]), super::nec_repeat(40_600), super::nec_repeat(96_200)], &[super::NecEvent::Code(super::NecCode { address: 0x00, command: 0x45 }), super::NecEvent::Repeat(super::NecCode { address: 0x00, command: 0x45 }), super::NecEvent::Repeat(super::NecCode { address: 0x00, command: 0x45 })]),
+        extended_address(&[super::nec_frame(50_000, [0x34, 0x12, 0x47, 0xb8])], &[super::NecEvent::Code(super::NecCode { address: 0x1234, command: 0x47 })]),
+        repeat_alone(&[super::nec_repeat(50_000)], &[]),
+        late_repeat(&[super::nec_frame(50_000, [0x00, 0xff, 0x45, 0xba]), super::nec_repeat(200_000)], &[super::NecEvent::Code(super::NecCode { address: 0x00, command: 0x45 })]),
+        corrupted(&[super::nec_frame(50_000, [0x00, 0xff, 0x45, 0xbb]), super::nec_repeat(40_600)], &[]),
+        noise(&[vec![(true, 300), (false, 7000), (true, 9040), (false, 900)], super::nec_frame(20_000, [0x00, 0xff, 0x09, 0xf6])], &[super::NecEvent::Code(super::NecCode { address: 0x00, command: 0x09 })]),
+    },
+    nec_receiver {
+        frame([0x00, 0xff, 0x07, 0xf8], crate::nec::NecCode { address: 0x00, command: 0x07 }),
+    },
+    remote_player {
+        press(&[Some(super::NecEvent::Code(super::NecCode { address: 0, command: 0x47 })), None, None, None, None, None, None, None, None, None], &[1, 1, 1, 1, 1, 1, 2, 1, 0, 0], (false, false)),
+        repeated(&[Some(super::NecEvent::Code(super::NecCode { address: 0, command: 0x45 })), None, None, None, None, None, Some(super::NecEvent::Repeat(super::NecCode { address: 0, command: 0x45 })), None, None, None, None, None, None, None, None, None], &[-1, -1, -1, -1, -1, -1, -2, -1, -1, -2, -1, -2, -1, -2, 0, 0], (false, false)),
+        serve(&[Some(super::NecEvent::Code(super::NecCode { address: 0, command: 0x46 }))], &[0], (true, false)),
+        pause(&[Some(super::NecEvent::Code(super::NecCode { address: 0, command: 0x43 })), None], &[0, 0], (false, true)),
+        other_remote(&[Some(super::NecEvent::Code(super::NecCode { address: 0x10, command: 0x47 })), None], &[0, 0], (false, false)),
+        stale(&[None, None], &[0, 0], (false, false)),
+    },
+    remote_players {
+        same_frame(&[&[super::NecEvent::Code(super::NecCode { address: 0, command: 0x47 }), super::NecEvent::Code(super::NecCode { address: 0, command: 0x07 })], &[], &[]], &[(1, -1), (1, -1), (1, -1)]),
+        interleaved(&[&[super::NecEvent::Code(super::NecCode { address: 0, command: 0x47 })], &[super::NecEvent::Code(super::NecCode { address: 0, command: 0x07 })], &[], &[], &[], &[], &[super::NecEvent::Repeat(super::NecCode { address: 0, command: 0x47 })], &[], &[], &[], &[]], &[(1, 0), (1, -1), (1, -1), (1, -1), (1, -1), (1, -1), (2, -1), (1, -2), (1, -1), (2, 0), (1, 0)]),
+        other_remote(&[&[super::NecEvent::Code(super::NecCode { address: 0, command: 0x47 })], &[super::NecEvent::Code(super::NecCode { address: 0x10, command: 0x15 })], &[super::NecEvent::Code(super::NecCode { address: 0, command: 0x09 })]], &[(1, 0), (1, 0), (1, 0)]),
+    },
+}
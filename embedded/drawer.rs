@@ -1,125 +1,1023 @@
 use {
-    display_interface::WriteOnlyDataCommand,
+    crate::{
+        layout::Layout,
+        skin::{self, Skin},
+    },
+    core::fmt::Debug,
+    display_interface::{DataFormat, WriteOnlyDataCommand},
+    drivers::{
+        burnin::BurnIn,
+        dma::{DmaDisplay, WriteDma},
+        indexed::{IndexedDisplay, PixelPanel},
+        menu::{Item, Menu, Mode, TEXT_LEN},
+        paged::PagedDisplay,
+        segments,
+    },
     embedded_graphics::{
-        mono_font::{ascii::FONT_8X13, MonoTextStyleBuilder},
-        pixelcolor::BinaryColor,
+        mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
+        pixelcolor::{BinaryColor, Rgb565},
         prelude::*,
         primitives::{Circle, PrimitiveStyleBuilder, Rectangle},
-        text::Text,
+        text::{Alignment, Baseline, Text, TextStyleBuilder},
+    },
+    numtoa::NumToA,
+    pong::{
+        effects::{Effects, TRAIL_LENGTH},
+        viewport::Viewport,
+        Event, Progress, Screen, Side, TextPosition,
     },
     ssd1306::{mode::BufferedGraphicsMode, prelude::*, Ssd1306},
 };
 
-pub struct Ssd1306PongDrawer<DisplayInterface, FieldSize>
+#[cfg(feature = "st7735")]
+use {
+    drivers::indexed::Frame,
+    embedded_hal::{blocking::delay::DelayMs, blocking::spi, digital::v2::OutputPin},
+    st7735_lcd::ST7735,
+};
+
+/// Colors the game is drawn with
+pub trait Palette: PixelColor {
+    const BACKGROUND: Self;
+    const FOREGROUND: Self;
+}
+
+impl Palette for BinaryColor {
+    const BACKGROUND: Self = BinaryColor::Off;
+    const FOREGROUND: Self = BinaryColor::On;
+}
+
+impl Palette for Rgb565 {
+    const BACKGROUND: Self = Rgb565::BLACK;
+    const FOREGROUND: Self = Rgb565::WHITE;
+}
+
+/// Colors of the parts of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colors<C> {
+    pub background: C,
+    /// Net, borders, score boxes and text
+    pub foreground: C,
+    pub ball: C,
+    pub paddles: [C; 2],
+    pub score: C,
+}
+
+impl<C> Colors<C>
+where
+    C: Palette,
+{
+    /// Everything in the foreground color of the display
+    pub const MONO: Self = Self {
+        background: C::BACKGROUND,
+        foreground: C::FOREGROUND,
+        ball: C::FOREGROUND,
+        paddles: [C::FOREGROUND, C::FOREGROUND],
+        score: C::FOREGROUND,
+    };
+}
+
+impl Colors<Rgb565> {
+    /// Red and blue players on a green table
+    pub const TABLE: Self = Self {
+        background: Rgb565::new(0, 24, 4),
+        foreground: Rgb565::WHITE,
+        ball: Rgb565::YELLOW,
+        paddles: [Rgb565::RED, Rgb565::BLUE],
+        score: Rgb565::WHITE,
+    };
+
+    /// Bright colors on black
+    pub const NEON: Self = Self {
+        background: Rgb565::BLACK,
+        foreground: Rgb565::new(8, 16, 8),
+        ball: Rgb565::WHITE,
+        paddles: [Rgb565::CYAN, Rgb565::MAGENTA],
+        score: Rgb565::GREEN,
+    };
+}
+
+/// Draw target that has to be told when a frame is complete
+pub trait Flush {
+    /// Returns how much was sent, bytes or pixels depending on the display
+    fn flush(&mut self) -> usize;
+}
+
+impl<DisplayInterface, FieldSize> Flush
+    for Ssd1306<DisplayInterface, FieldSize, BufferedGraphicsMode<FieldSize>>
 where
     DisplayInterface: WriteOnlyDataCommand,
     FieldSize: DisplaySize,
 {
-    display: Ssd1306<DisplayInterface, FieldSize, BufferedGraphicsMode<FieldSize>>,
+    fn flush(&mut self) -> usize {
+        Ssd1306::flush(self).unwrap();
+        FieldSize::WIDTH as usize * FieldSize::HEIGHT as usize / 8
+    }
 }
 
-impl<DisplayInterface, FieldSize> Ssd1306PongDrawer<DisplayInterface, FieldSize>
+/// Display that can move the picture and change its brightness against
+/// burn-in
+pub trait BurnInTarget {
+    fn set_shift(&mut self, shift: (i32, i32));
+    fn set_contrast(&mut self, contrast: u8);
+}
+
+impl<DisplayInterface> BurnInTarget for PagedDisplay<DisplayInterface>
 where
     DisplayInterface: WriteOnlyDataCommand,
+{
+    fn set_shift(&mut self, shift: (i32, i32)) {
+        PagedDisplay::set_shift(self, shift);
+    }
+
+    fn set_contrast(&mut self, contrast: u8) {
+        PagedDisplay::set_contrast(self, contrast).ok().unwrap();
+    }
+}
+
+impl<Writer> BurnInTarget for DmaDisplay<Writer>
+where
+    Writer: WriteDma,
+{
+    fn set_shift(&mut self, shift: (i32, i32)) {
+        DmaDisplay::set_shift(self, shift);
+    }
+
+    fn set_contrast(&mut self, contrast: u8) {
+        DmaDisplay::set_contrast(self, contrast);
+    }
+}
+
+/// TFTs don't burn in
+impl<Panel> BurnInTarget for IndexedDisplay<Panel>
+where
+    Panel: PixelPanel,
+{
+    fn set_shift(&mut self, _: (i32, i32)) {}
+    fn set_contrast(&mut self, _: u8) {}
+}
+
+/// How the game looks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// Round ball and boxed score
+    Modern,
+    /// Square ball, dashed net and block digits like the original arcade
+    /// game, `borders` draws lines along the field edges the ball bounces off
+    Arcade { borders: bool },
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Modern
+    }
+}
+
+pub struct PongDrawer<Target>
+where
+    Target: DrawTarget,
+    Target::Color: Palette,
+{
+    target: Target,
+    layout: Layout,
+    viewport: Viewport,
+    field: (u32, u32),
+    theme: Theme,
+    colors: Colors<Target::Color>,
+    skin: Skin,
+    effects: Effects,
+    names: [[u8; TEXT_LEN]; 2],
+    /// Sent by the frames since the last screen and their count
+    #[cfg(feature = "measure-flush")]
+    flushed: (usize, usize),
+}
+
+impl<DisplayInterface> Flush for PagedDisplay<DisplayInterface>
+where
+    DisplayInterface: WriteOnlyDataCommand,
+{
+    fn flush(&mut self) -> usize {
+        PagedDisplay::flush(self).unwrap()
+    }
+}
+
+impl<Writer> Flush for DmaDisplay<Writer>
+where
+    Writer: WriteDma,
+{
+    fn flush(&mut self) -> usize {
+        DmaDisplay::flush(self)
+    }
+}
+
+impl<Panel> Flush for IndexedDisplay<Panel>
+where
+    Panel: PixelPanel,
+    Panel::Error: Debug,
+{
+    fn flush(&mut self) -> usize {
+        IndexedDisplay::flush(self).unwrap()
+    }
+}
+
+/// ST7735 driver as a `PixelPanel`
+#[cfg(feature = "st7735")]
+pub struct St7735Panel<SPI, DC, RST>(ST7735<SPI, DC, RST>)
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin;
+
+#[cfg(feature = "st7735")]
+impl<SPI, DC, RST> PixelPanel for St7735Panel<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+{
+    type Error = ();
+
+    fn set_pixels<P>(&mut self, start: (u16, u16), end: (u16, u16), colors: P) -> Result<(), ()>
+    where
+        P: IntoIterator<Item = u16>,
+    {
+        self.0.set_pixels(start.0, start.1, end.0, end.1, colors)
+    }
+}
+
+#[cfg(feature = "st7735")]
+pub type St7735PongDrawer<SPI, DC, RST> = PongDrawer<IndexedDisplay<St7735Panel<SPI, DC, RST>>>;
+
+/// 160x128 colour TFT behind a frame buffer that sends only what changed,
+/// `cs` has to be held low
+#[cfg(feature = "st7735")]
+pub fn with_st7735<SPI, DC, RST, Delay>(
+    spi: SPI,
+    dc: DC,
+    rst: RST,
+    delay: &mut Delay,
+    frame: &'static mut Frame,
+    rotation: DisplayRotation,
+) -> St7735PongDrawer<SPI, DC, RST>
+where
+    SPI: spi::Write<u8>,
+    DC: OutputPin,
+    RST: OutputPin,
+    Delay: DelayMs<u8>,
+{
+    use st7735_lcd::Orientation;
+
+    let (orientation, (width, height)) = match rotation {
+        DisplayRotation::Rotate0 => (Orientation::Landscape, (160, 128)),
+        DisplayRotation::Rotate90 => (Orientation::Portrait, (128, 160)),
+        DisplayRotation::Rotate180 => (Orientation::LandscapeSwapped, (160, 128)),
+        DisplayRotation::Rotate270 => (Orientation::PortraitSwapped, (128, 160)),
+    };
+
+    let mut display = ST7735::new(spi, dc, rst, true, false, width, height);
+    display.init(delay).unwrap();
+    display.set_orientation(&orientation).unwrap();
+
+    let panel = St7735Panel(display);
+    PongDrawer::new(IndexedDisplay::new(
+        panel,
+        frame,
+        width as u16,
+        height as u16,
+    ))
+}
+
+pub type Ssd1306PongDrawer<DisplayInterface> = PongDrawer<PagedDisplay<DisplayInterface>>;
+
+/// Initializes the display the way `Ssd1306::init` does and sets its address
+/// window to the whole screen, the interface stays with the caller
+pub fn init_ssd1306<DisplayInterface, FieldSize>(
+    mut interface: DisplayInterface,
+    size: FieldSize,
+    rotation: DisplayRotation,
+) -> DisplayInterface
+where
+    DisplayInterface: WriteOnlyDataCommand,
+    FieldSize: DisplaySize,
+{
+    use ssd1306::command::{AddrMode, Command, VcomhLevel};
+
+    let (remap, reverse) = match rotation {
+        DisplayRotation::Rotate0 => (true, true),
+        DisplayRotation::Rotate90 => (false, true),
+        DisplayRotation::Rotate180 => (false, false),
+        DisplayRotation::Rotate270 => (true, false),
+    };
+
+    let commands = [
+        Command::DisplayOn(false),
+        Command::DisplayClockDiv(0x8, 0x0),
+        Command::Multiplex(FieldSize::HEIGHT - 1),
+        Command::DisplayOffset(0),
+        Command::StartLine(0),
+        Command::ChargePump(true),
+        Command::AddressMode(AddrMode::Horizontal),
+    ];
+    for command in commands.iter() {
+        command.send(&mut interface).ok().unwrap();
+    }
+    size.configure(&mut interface).ok().unwrap();
+
+    let commands = [
+        Command::SegmentRemap(remap),
+        Command::ReverseComDir(reverse),
+        Command::PreChargePeriod(1, 0x2),
+        Command::Contrast(0x5f),
+        Command::VcomhDeselect(VcomhLevel::Auto),
+        Command::AllOn(false),
+        Command::Invert(false),
+        Command::EnableScroll(false),
+        Command::DisplayOn(true),
+    ];
+    for command in commands.iter() {
+        command.send(&mut interface).ok().unwrap();
+    }
+
+    let (column, page) = (FieldSize::OFFSETX, FieldSize::OFFSETY / 8);
+    interface
+        .send_commands(DataFormat::U8(&[
+            0x21,
+            column,
+            column + FieldSize::WIDTH - 1,
+            0x22,
+            page,
+            page + FieldSize::HEIGHT / 8 - 1,
+        ]))
+        .ok()
+        .unwrap();
+
+    interface
+}
+
+fn is_transposed(rotation: DisplayRotation) -> bool {
+    matches!(
+        rotation,
+        DisplayRotation::Rotate90 | DisplayRotation::Rotate270
+    )
+}
+
+/// Sends only the changed parts of every frame to the display
+pub fn with_ssd1306<DisplayInterface, FieldSize>(
+    interface: DisplayInterface,
+    size: FieldSize,
+    rotation: DisplayRotation,
+) -> Ssd1306PongDrawer<DisplayInterface>
+where
+    DisplayInterface: WriteOnlyDataCommand,
+    FieldSize: DisplaySize,
+{
+    let display = PagedDisplay::new(
+        init_ssd1306(interface, size, rotation),
+        FieldSize::WIDTH,
+        FieldSize::HEIGHT,
+        (FieldSize::OFFSETX, FieldSize::OFFSETY / 8),
+        is_transposed(rotation),
+    );
+
+    PongDrawer::new(display)
+}
+
+/// Sends whole frames in the background, the display has to be initialized
+/// with `init_ssd1306` before the bus is handed over to `writer`
+pub fn with_dma<Writer, FieldSize>(
+    writer: Writer,
+    back: &'static mut [u8],
+    _size: FieldSize,
+    rotation: DisplayRotation,
+) -> PongDrawer<DmaDisplay<Writer>>
+where
+    Writer: WriteDma,
     FieldSize: DisplaySize,
 {
-    pub fn new(interface: DisplayInterface, size: FieldSize) -> Self {
-        let mut display =
-            Ssd1306::new(interface, size, DisplayRotation::Rotate0).into_buffered_graphics_mode();
-        display.init().ok().unwrap();
+    let display = DmaDisplay::new(
+        writer,
+        back,
+        FieldSize::WIDTH,
+        FieldSize::HEIGHT,
+        is_transposed(rotation),
+    );
+
+    PongDrawer::new(display)
+}
+
+fn fit(area: Rectangle, field: (u32, u32)) -> Viewport {
+    Viewport::fit(
+        field,
+        (area.top_left.x, area.top_left.y),
+        (area.size.width, area.size.height),
+    )
+}
+
+impl<Target> PongDrawer<Target>
+where
+    Target: DrawTarget,
+    Target::Color: Palette,
+    Target::Error: Debug,
+{
+    pub fn new(target: Target) -> Self {
+        let layout = Layout::new(target.bounding_box());
+        let field = (pong::FIELD_WIDTH, pong::FIELD_HEIGHT);
+        let viewport = fit(layout.field, field);
+
+        Self {
+            target,
+            layout,
+            viewport,
+            field,
+            theme: Theme::default(),
+            colors: Colors::MONO,
+            skin: Skin::NONE,
+            effects: Effects::new(),
+            names: [[b' '; TEXT_LEN]; 2],
+            #[cfg(feature = "measure-flush")]
+            flushed: (0, 0),
+        }
+    }
+
+    /// Prints the average flush of the rally that just ended, screens are
+    /// redrawn every frame so while one stays up only its own frame counts
+    #[cfg(feature = "measure-flush")]
+    fn report_flushed(&mut self) {
+        let (sent, frames) = core::mem::take(&mut self.flushed);
+        if frames > 1 {
+            let _ = cortex_m_semihosting::hprintln!(
+                "flush: {} per frame over {} frames",
+                sent / frames,
+                frames
+            );
+        }
+    }
+
+    /// Names of both players, blank ones are shown as "PLAYER 1" and
+    /// "PLAYER 2"
+    pub fn set_names(&mut self, names: [[u8; TEXT_LEN]; 2]) {
+        self.names = names;
+    }
+
+    pub fn fit_field(&mut self, field: (u32, u32)) {
+        self.field = field;
+        self.viewport = fit(self.layout.field, field);
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    pub fn set_colors(&mut self, colors: Colors<Target::Color>) {
+        self.colors = colors;
+    }
+
+    pub fn set_skin(&mut self, skin: Skin) {
+        self.skin = skin;
+    }
+
+    /// Advances the effects by one frame, call once per game frame before
+    /// drawing it
+    pub fn update_effects(&mut self, progress: &Progress, event: Option<Event>) {
+        self.effects.update(progress, event);
+    }
+
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn effects_running(&self) -> bool {
+        self.effects.active().next().is_some()
+    }
+
+    /// `color` as it is drawn in this frame, a flash turns everything into
+    /// the background color over a foreground background
+    fn paint(&self, color: Target::Color) -> Target::Color {
+        if self.effects.inverted() {
+            self.colors.background
+        } else {
+            color
+        }
+    }
+
+    fn foreground(&self) -> Target::Color {
+        self.paint(self.colors.foreground)
+    }
+
+    fn background(&self) -> Target::Color {
+        if self.effects.inverted() {
+            self.colors.foreground
+        } else {
+            self.colors.background
+        }
+    }
+
+    /// Viewport of the field moved by the screen shake
+    fn view(&self) -> Viewport {
+        let (dx, dy) = self.effects.shake();
+        self.viewport.shifted(dx, dy)
+    }
+
+    fn is_landscape(&self) -> bool {
+        self.field.0 >= self.field.1
+    }
+
+    /// Field on the screen, whether the ball travels along its width and
+    /// the thickness of the net and borders
+    fn field_area(&self) -> (Rectangle, bool, u32) {
+        let view = self.view();
+        let ((x, y), (width, height)) = view.field(self.field);
+        let thickness = view.length(self.field.0.min(self.field.1) / 64);
 
-        Self { display }
+        (
+            Rectangle::new(Point::new(x, y), Size::new(width, height)),
+            self.is_landscape(),
+            thickness,
+        )
+    }
+
+    /// Score in block digits, centered in `area`
+    fn draw_digits(&mut self, score: u32, area: Rectangle) {
+        let height = area.size.height.min(area.size.width * 4 / 5);
+        let (size, gap) = match self.skin_digits(area) {
+            Some(size) => (size, 0),
+            None => (Size::new(height / 2, height), height / 8),
+        };
+        let count = if score >= 10 { 2 } else { 1 };
+        let total = count * size.width + (count - 1) * gap;
+
+        let top_left = area.center() - Point::new(total as i32 / 2, size.height as i32 / 2);
+        let values = [score / 10, score];
+        let color = self.paint(self.colors.score);
+
+        for (index, &value) in values[2 - count as usize..].iter().enumerate() {
+            let offset = Point::new((index as u32 * (size.width + gap)) as i32, 0);
+            let cell = Rectangle::new(top_left + offset, size);
+
+            if let Some(points) = skin::digit_pixels(&self.skin, value, size, cell.top_left) {
+                let pixels = points.map(|point| Pixel(point, color));
+                self.target.draw_iter(pixels).unwrap();
+                continue;
+            }
+
+            for segment in segments::digit(value, cell) {
+                self.draw_rect(segment.top_left, segment.size, color);
+            }
+        }
     }
 
-    pub fn clear(&mut self) {
-        Rectangle::new(Point::new(0, 0), Size::new(128, 64))
+    /// Size of the skin digits if two of them fit inside the border of a
+    /// score box
+    fn skin_digits(&self, area: Rectangle) -> Option<Size> {
+        self.skin.digit_size().filter(|size| {
+            size.width * 2 + 2 <= area.size.width && size.height + 2 <= area.size.height
+        })
+    }
+
+    /// Outlines of the ball at its previous positions, smaller the older
+    /// they are
+    fn draw_trail(&mut self, ball: &pong::Ball) {
+        let view = self.view();
+        let style = PrimitiveStyleBuilder::new()
+            .stroke_color(self.paint(self.colors.ball))
+            .stroke_width(1)
+            .build();
+        let trail = self.effects.trail();
+
+        for (age, (x, y)) in trail.enumerate() {
+            let diameter = view.length(ball.r * 2) * (age as u32 + 1) / (TRAIL_LENGTH as u32 + 1);
+            if diameter < 2 {
+                continue;
+            }
+            let (x, y) = view.point(x, y);
+            let center = Point::new(x, y);
+            Circle::with_center(center, diameter)
+                .into_styled(style)
+                .draw(&mut self.target)
+                .unwrap();
+        }
+    }
+
+    /// Dot on the net side of a paddle whose player has a handicap, as
+    /// thick as the paddle and one thickness away from it
+    fn draw_handicap(
+        &mut self,
+        paddle: ((i32, i32), (u32, u32)),
+        first_half: bool,
+        color: Target::Color,
+    ) {
+        let ((x, y), (width, height)) = paddle;
+        let top_left = if self.is_landscape() {
+            let gap = 2 * width as i32;
+            let x = if first_half { x + gap } else { x - gap };
+            Point::new(x, y + (height / 2) as i32 - (width / 2) as i32)
+        } else {
+            let gap = 2 * height as i32;
+            let y = if first_half { y + gap } else { y - gap };
+            Point::new(x + (width / 2) as i32 - (height / 2) as i32, y)
+        };
+        let thickness = width.min(height);
+        self.draw_rect(top_left, Size::new(thickness, thickness), color);
+    }
+
+    fn draw_rect(&mut self, top_left: Point, size: Size, color: Target::Color) {
+        Rectangle::new(top_left, size)
             .into_styled(
                 PrimitiveStyleBuilder::new()
-                    .fill_color(BinaryColor::Off)
-                    .stroke_color(BinaryColor::Off)
+                    .fill_color(color)
+                    .stroke_width(0)
                     .build(),
             )
-            .draw(&mut self.display)
+            .draw(&mut self.target)
             .unwrap();
     }
+}
 
-    fn draw_rect(&mut self, top_left: Point, size: Size) {
-        Rectangle::new(top_left, size)
+impl<Target> PongDrawer<Target>
+where
+    Target: DrawTarget + Flush + BurnInTarget,
+    Target::Color: Palette,
+    Target::Error: Debug,
+{
+    /// Moves the picture and sets the contrast for the current burn-in state
+    pub fn protect(&mut self, burn_in: &BurnIn) {
+        self.target.set_shift(burn_in.shift());
+        self.target.set_contrast(burn_in.contrast());
+    }
+
+    /// Draws a frame of the screensaver: the title bouncing around
+    pub fn draw_screensaver(&mut self, burn_in: &BurnIn) {
+        use pong::Drawer;
+
+        let text = "PONG";
+        let area = self.target.bounding_box();
+        let text_size = Size::new(
+            FONT_6X10.character_size.width * text.len() as u32,
+            FONT_6X10.character_size.height,
+        );
+        let range = area.size.saturating_sub(text_size);
+        let (x, y) = burn_in.screensaver_position((range.width, range.height));
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X10)
+            .text_color(self.colors.foreground)
+            .build();
+
+        self.begin_frame();
+        Text::with_baseline(
+            text,
+            area.top_left + Point::new(x, y),
+            character_style,
+            Baseline::Top,
+        )
+        .draw(&mut self.target)
+        .unwrap();
+        self.end_frame();
+    }
+}
+
+impl<Target> PongDrawer<Target>
+where
+    Target: DrawTarget + Flush,
+    Target::Color: Palette,
+    Target::Error: Debug,
+{
+    /// Draws the selected entry of a menu: its label, its value between
+    /// arrows while it's changed, and the position in the menu
+    /// Asks a player to turn their control as far as it should move the
+    /// paddle from the bottom to the top, with the counts turned so far
+    pub fn draw_calibration(&mut self, side: Side, counts: i32) {
+        use pong::Drawer;
+
+        let mut buffer = [0u8; 10];
+        let name = self.names[side as usize];
+
+        self.begin_frame();
+        self.draw_text(player_name(&name, side), TextPosition::Top);
+        self.draw_text(
+            counts.unsigned_abs().numtoa_str(10, &mut buffer),
+            TextPosition::Center,
+        );
+        self.draw_text("turn up, press", TextPosition::Bottom);
+        self.end_frame();
+    }
+
+    pub fn draw_menu<const N: usize>(&mut self, menu: &Menu<N>) {
+        use pong::Drawer;
+
+        let entry = menu.entries()[menu.selected()];
+        let mut value = [0u8; 11];
+        let mut buffer = [0u8; 21];
+
+        self.begin_frame();
+        match (entry.item, menu.mode()) {
+            (Item::Action, _) => self.draw_text(entry.label, TextPosition::Center),
+            (item, Mode::Browsing) => {
+                self.draw_text(entry.label, TextPosition::Top);
+                self.draw_text(item.text(&mut value), TextPosition::Center);
+            }
+            (item, Mode::Editing) => {
+                let text = join(&["< ", item.text(&mut value), " >"], &mut buffer);
+                self.draw_text(entry.label, TextPosition::Top);
+                self.draw_text(text, TextPosition::Center);
+            }
+            (item, Mode::EditingText(cursor)) => {
+                let text = item.text(&mut value);
+                let cursor = cursor.min(text.len());
+                let (before, after) = text.split_at(cursor);
+                let (current, after) = after.split_at(after.len().min(1));
+                let text = join(&[before, "[", current, "]", after], &mut buffer);
+                self.draw_text(entry.label, TextPosition::Top);
+                self.draw_text(text, TextPosition::Center);
+            }
+        }
+
+        let mut number = [0u8; 10];
+        let mut count = [0u8; 10];
+        let position = join(
+            &[
+                (menu.selected() as u32 + 1).numtoa_str(10, &mut number),
+                "/",
+                (N as u32).numtoa_str(10, &mut count),
+            ],
+            &mut buffer,
+        );
+        self.draw_text(position, TextPosition::Bottom);
+        self.end_frame();
+    }
+}
+
+impl<Target> pong::Drawer for PongDrawer<Target>
+where
+    Target: DrawTarget + Flush,
+    Target::Color: Palette,
+    Target::Error: Debug,
+{
+    fn begin_frame(&mut self) {
+        self.target.clear(self.background()).unwrap();
+    }
+
+    fn end_frame(&mut self) {
+        let _flushed = self.target.flush();
+
+        #[cfg(feature = "measure-flush")]
+        {
+            self.flushed.0 += _flushed;
+            self.flushed.1 += 1;
+        }
+    }
+
+    fn draw_ball(&mut self, ball: &pong::Ball) {
+        self.draw_trail(ball);
+
+        let ((x, y), diameter) = self.view().ball(ball);
+        let top_left = Point::new(x, y);
+        let color = self.paint(self.colors.ball);
+        let size = Size::new(diameter, diameter);
+        if let Some(points) = skin::sprite_pixels(&self.skin.ball, size, top_left) {
+            let pixels = points.map(|point| Pixel(point, color));
+            return self.target.draw_iter(pixels).unwrap();
+        }
+
+        if let Theme::Arcade { .. } = self.theme {
+            return self.draw_rect(top_left, size, color);
+        }
+
+        Circle::new(top_left, diameter)
             .into_styled(
                 PrimitiveStyleBuilder::new()
-                    .fill_color(BinaryColor::On)
-                    .stroke_width(0)
+                    .fill_color(color)
+                    .stroke_color(color)
                     .build(),
             )
-            .draw(&mut self.display)
+            .draw(&mut self.target)
             .unwrap();
     }
 
-    pub fn draw_score(&mut self, score: &(u32, u32)) {
-        use numtoa::NumToA;
+    fn draw_player(&mut self, player: &pong::Player) {
+        let ((x, y), (width, height)) = self.view().player(player);
+        let first_half = if self.is_landscape() {
+            (player.x as u32) < self.field.0 / 2
+        } else {
+            (player.y as u32) < self.field.1 / 2
+        };
+        let (side, index) = if first_half {
+            (Side::Player1, 0)
+        } else {
+            (Side::Player2, 1)
+        };
+        let color = self.paint(self.colors.paddles[index]);
+
+        if player.handicap != pong::Handicap::NONE {
+            self.draw_handicap(((x, y), (width, height)), first_half, color);
+        }
+
+        if self.effects.paddle_flash(side) {
+            let style = PrimitiveStyleBuilder::new()
+                .stroke_color(color)
+                .stroke_width(1)
+                .build();
+            Rectangle::new(Point::new(x, y), Size::new(width, height))
+                .into_styled(style)
+                .draw(&mut self.target)
+                .unwrap();
+            return;
+        }
+
+        let (top_left, size) = (Point::new(x, y), Size::new(width, height));
+        if let Some(points) = skin::sprite_pixels(&self.skin.paddle, size, top_left) {
+            let pixels = points.map(|point| Pixel(point, color));
+            return self.target.draw_iter(pixels).unwrap();
+        }
+        self.draw_rect(top_left, size, color);
+    }
+
+    fn draw_net(&mut self) {
+        if self.theme == Theme::Modern {
+            return;
+        }
+
+        let (field, landscape, thickness) = self.field_area();
+        let center = field.center();
+        let color = self.foreground();
+
+        if landscape {
+            let dash = (field.size.height / 16).max(2);
+            let x = center.x - thickness as i32 / 2;
+            for y in (0..field.size.height).step_by(2 * dash as usize) {
+                let length = dash.min(field.size.height - y);
+                let top = Point::new(x, field.top_left.y + y as i32);
+                self.draw_rect(top, Size::new(thickness, length), color);
+            }
+        } else {
+            let dash = (field.size.width / 16).max(2);
+            let y = center.y - thickness as i32 / 2;
+            for x in (0..field.size.width).step_by(2 * dash as usize) {
+                let length = dash.min(field.size.width - x);
+                let left = Point::new(field.top_left.x + x as i32, y);
+                self.draw_rect(left, Size::new(length, thickness), color);
+            }
+        }
+    }
+
+    fn draw_borders(&mut self) {
+        if self.theme != (Theme::Arcade { borders: true }) {
+            return;
+        }
+
+        let (field, landscape, thickness) = self.field_area();
+        let bottom_right = field.top_left + field.size - Size::new(thickness, thickness);
+        let color = self.foreground();
+
+        if landscape {
+            let size = Size::new(field.size.width, thickness);
+            self.draw_rect(field.top_left, size, color);
+            self.draw_rect(Point::new(field.top_left.x, bottom_right.y), size, color);
+        } else {
+            let size = Size::new(thickness, field.size.height);
+            self.draw_rect(field.top_left, size, color);
+            self.draw_rect(Point::new(bottom_right.x, field.top_left.y), size, color);
+        }
+    }
+
+    fn draw_score(&mut self, score: &(u32, u32)) {
+        let areas = self.layout.scores;
+        let skinned = self.skin_digits(areas[0]).is_some();
+
+        if let Theme::Arcade { .. } = self.theme {
+            self.draw_digits(score.0, areas[0]);
+            self.draw_digits(score.1, areas[1]);
+            return;
+        }
+
         let mut data1 = [0u8; 10];
         let mut data2 = [0u8; 10];
         let text1 = score.0.numtoa_str(10, &mut data1);
         let text2 = score.1.numtoa_str(10, &mut data2);
 
         let style = PrimitiveStyleBuilder::new()
-            .fill_color(BinaryColor::Off)
-            .stroke_color(BinaryColor::On)
+            .fill_color(self.background())
+            .stroke_color(self.foreground())
             .stroke_width(1)
             .build();
+        let character_style = MonoTextStyleBuilder::new()
+            .font(self.layout.font)
+            .text_color(self.paint(self.colors.score))
+            .build();
+        let text_style = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Middle)
+            .build();
 
-        Rectangle::new(Point::new(38, 0), Size::new(26, 15))
-            .into_styled(style)
-            .draw(&mut self.display)
-            .unwrap();
-        Rectangle::new(Point::new(64, 0), Size::new(26, 15))
-            .into_styled(style)
-            .draw(&mut self.display)
-            .unwrap();
+        for (text, area) in [text1, text2].iter().zip(areas.iter()) {
+            area.into_styled(style).draw(&mut self.target).unwrap();
+            if skinned {
+                continue;
+            }
+            Text::with_text_style(text, area.center(), character_style, text_style)
+                .draw(&mut self.target)
+                .unwrap();
+        }
 
-        let style = MonoTextStyleBuilder::new()
-            .font(&FONT_8X13)
-            .text_color(BinaryColor::On)
+        if skinned {
+            self.draw_digits(score.0, areas[0]);
+            self.draw_digits(score.1, areas[1]);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, position: TextPosition) {
+        let area = self.target.bounding_box();
+        let center = area.center();
+        let bottom_right = area.bottom_right().unwrap_or(area.top_left);
+
+        let (position, baseline) = match position {
+            TextPosition::Top => (Point::new(center.x, area.top_left.y), Baseline::Top),
+            TextPosition::Center => (center, Baseline::Middle),
+            TextPosition::Bottom => (Point::new(center.x, bottom_right.y), Baseline::Bottom),
+        };
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X10)
+            .text_color(self.foreground())
+            .background_color(self.background())
+            .build();
+        let text_style = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(baseline)
             .build();
 
-        Text::new(text1, Point::new(40, 11), style)
-            .draw(&mut self.display)
+        Text::with_text_style(text, position, character_style, text_style)
+            .draw(&mut self.target)
             .unwrap();
+    }
 
-        Text::new(text2, Point::new(66, 11), style)
-            .draw(&mut self.display)
-            .unwrap();
+    fn draw_screen(&mut self, screen: &Screen) {
+        #[cfg(feature = "measure-flush")]
+        self.report_flushed();
+
+        match screen {
+            Screen::Splash => {
+                self.draw_text("PONG", TextPosition::Center);
+                self.draw_text("press to play", TextPosition::Bottom);
+            }
+            Screen::Goal(scorer, score) => {
+                let name = self.names[*scorer as usize];
+                self.draw_score(score);
+                self.draw_text(player_name(&name, *scorer), TextPosition::Center);
+                self.draw_text("SCORES", TextPosition::Bottom);
+            }
+            Screen::MatchOver(winner, score) => {
+                let mut buffer = [0u8; 21];
+                let name = self.names[*winner as usize];
+                self.draw_text(player_name(&name, *winner), TextPosition::Top);
+                self.draw_text("WINS", TextPosition::Center);
+                self.draw_text(score_text(score, &mut buffer), TextPosition::Bottom);
+            }
+        }
     }
+}
 
-    pub fn flush(&mut self) {
-        self.display.flush().unwrap();
+fn player_name(name: &[u8; TEXT_LEN], side: Side) -> &str {
+    match core::str::from_utf8(name).unwrap_or_default().trim() {
+        "" => match side {
+            Side::Player1 => "PLAYER 1",
+            Side::Player2 => "PLAYER 2",
+        },
+        name => name,
     }
 }
 
-impl<DisplayInterface, FieldSize> pong::Drawer for Ssd1306PongDrawer<DisplayInterface, FieldSize>
-where
-    DisplayInterface: WriteOnlyDataCommand,
-    FieldSize: DisplaySize,
-{
-    fn draw_ball(&mut self, ball: &pong::Ball) {
-        Circle::new(
-            Point::new(ball.x as i32 - ball.r as i32, ball.y as i32 - ball.r as i32),
-            ball.r * 2,
-        )
-        .into_styled(
-            PrimitiveStyleBuilder::new()
-                .fill_color(BinaryColor::On)
-                .stroke_color(BinaryColor::On)
-                .build(),
-        )
-        .draw(&mut self.display)
-        .unwrap();
+/// Parts after each other, cut off when they don't fit
+fn join<'a>(parts: &[&str], buffer: &'a mut [u8; 21]) -> &'a str {
+    let mut len = 0;
+    for part in parts {
+        let part = part.as_bytes();
+        let count = part.len().min(buffer.len() - len);
+        buffer[len..len + count].copy_from_slice(&part[..count]);
+        len += count;
     }
 
-    fn draw_player(&mut self, player: &pong::Player) {
-        self.draw_rect(
-            Point::new(player.x, player.y),
-            Size::new(player.width, player.height),
-        )
+    core::str::from_utf8(&buffer[..len]).unwrap_or_default()
+}
+
+/// Score as "11-7"
+fn score_text<'a>(score: &(u32, u32), buffer: &'a mut [u8; 21]) -> &'a str {
+    let mut number = [0u8; 10];
+    let mut len = 0;
+
+    for (index, &value) in [score.0, score.1].iter().enumerate() {
+        if index > 0 {
+            buffer[len] = b'-';
+            len += 1;
+        }
+        let text = value.numtoa_str(10, &mut number).as_bytes();
+        buffer[len..len + text.len()].copy_from_slice(text);
+        len += text.len();
     }
+
+    core::str::from_utf8(&buffer[..len]).unwrap_or_default()
 }
@@ -0,0 +1,92 @@
+use embedded_graphics::{
+    mono_font::{
+        ascii::{FONT_4X6, FONT_6X10, FONT_8X13},
+        MonoFont,
+    },
+    prelude::*,
+    primitives::Rectangle,
+};
+
+/// Places the scoreboard and the field on the display. Large displays show
+/// the score over the field, smaller ones keep it apart so it never covers
+/// the ball
+pub struct Layout {
+    pub field: Rectangle,
+    pub scores: [Rectangle; 2],
+    pub font: &'static MonoFont<'static>,
+}
+
+/// Widest score that fits in a score box
+const SCORE_DIGITS: u32 = 2;
+
+impl Layout {
+    pub fn new(area: Rectangle) -> Self {
+        let Size { width, height } = area.size;
+
+        if height >= 64 && width >= 2 * Self::score_size(&FONT_8X13).width {
+            Self::overlay(area, &FONT_8X13)
+        } else if width >= 4 * height {
+            Self::sides(area, if height >= 32 { &FONT_6X10 } else { &FONT_4X6 })
+        } else {
+            Self::band(area, if width >= 64 { &FONT_6X10 } else { &FONT_4X6 })
+        }
+    }
+
+    fn score_size(font: &MonoFont) -> Size {
+        let character = font.character_size;
+        let padding = character.width * 5 / 8;
+        Size::new(
+            SCORE_DIGITS * character.width + 2 * padding,
+            character.height + 2,
+        )
+    }
+
+    /// Score boxes at the top center, drawn over the field
+    fn overlay(area: Rectangle, font: &'static MonoFont<'static>) -> Self {
+        let size = Self::score_size(font);
+        let center = area.center().x;
+
+        Self {
+            field: area,
+            scores: [
+                Rectangle::new(
+                    Point::new(center - size.width as i32, area.top_left.y),
+                    size,
+                ),
+                Rectangle::new(Point::new(center, area.top_left.y), size),
+            ],
+            font,
+        }
+    }
+
+    /// Score boxes at the left and right edges, the field between them
+    fn sides(area: Rectangle, font: &'static MonoFont<'static>) -> Self {
+        let size = Size::new(Self::score_size(font).width, area.size.height);
+        let right = area.top_left.x + (area.size.width - size.width) as i32;
+
+        Self {
+            field: Rectangle::new(
+                area.top_left + Point::new(size.width as i32, 0),
+                Size::new(area.size.width - 2 * size.width, area.size.height),
+            ),
+            scores: [
+                Rectangle::new(area.top_left, size),
+                Rectangle::new(Point::new(right, area.top_left.y), size),
+            ],
+            font,
+        }
+    }
+
+    /// Score boxes in a band at the top, the field below it
+    fn band(area: Rectangle, font: &'static MonoFont<'static>) -> Self {
+        let size = Self::score_size(font);
+
+        Self {
+            field: Rectangle::new(
+                area.top_left + Point::new(0, size.height as i32),
+                Size::new(area.size.width, area.size.height - size.height),
+            ),
+            ..Self::overlay(area, font)
+        }
+    }
+}
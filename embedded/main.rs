@@ -7,109 +7,698 @@
 ))]
 compile_error!("select only one of platforms: stm32f103, stm32f401");
 
+#[cfg(any(
+    all(feature = "display-128x32", feature = "display-96x16"),
+    all(feature = "display-128x32", feature = "display-64x48"),
+    all(feature = "display-96x16", feature = "display-64x48"),
+))]
+compile_error!("select only one of displays: display-128x32, display-96x16, display-64x48");
+
+#[cfg(all(
+    feature = "st7735",
+    any(
+        feature = "dma",
+        feature = "display-128x32",
+        feature = "display-96x16",
+        feature = "display-64x48",
+    ),
+))]
+compile_error!("st7735 can't be combined with dma or ssd1306 display sizes");
+
+#[cfg(all(feature = "i2c", any(feature = "dma", feature = "st7735")))]
+compile_error!("i2c can't be combined with dma or st7735");
+
+#[cfg(any(
+    all(feature = "player1-pot", feature = "player1-buttons"),
+    all(feature = "player1-pot", feature = "player1-nunchuk"),
+    all(feature = "player1-pot", feature = "player1-remote"),
+    all(feature = "player1-buttons", feature = "player1-nunchuk"),
+    all(feature = "player1-buttons", feature = "player1-remote"),
+    all(feature = "player1-nunchuk", feature = "player1-remote"),
+))]
+compile_error!(
+    "select only one control for player 1: player1-pot, player1-buttons, player1-nunchuk, player1-remote"
+);
+
+#[cfg(any(
+    all(feature = "player2-pot", feature = "player2-buttons"),
+    all(feature = "player2-pot", feature = "player2-nunchuk"),
+    all(feature = "player2-pot", feature = "player2-remote"),
+    all(feature = "player2-buttons", feature = "player2-nunchuk"),
+    all(feature = "player2-buttons", feature = "player2-remote"),
+    all(feature = "player2-nunchuk", feature = "player2-remote"),
+))]
+compile_error!(
+    "select only one control for player 2: player2-pot, player2-buttons, player2-nunchuk, player2-remote"
+);
+
+#[cfg(all(
+    feature = "player1-nunchuk",
+    feature = "player2-nunchuk",
+    feature = "i2c"
+))]
+compile_error!("the nunchuk of player 2 needs I2C1, it can't be combined with i2c");
+
+#[cfg(all(feature = "rotate90", feature = "rotate270"))]
+compile_error!("select only one of rotations: rotate90, rotate270");
+
 use {
-    control::{PlayerControl, PlayerEncoder},
     cortex_m_rt::{entry, exception, ExceptionFrame},
+    drivers::{
+        beeper::Beeper,
+        burnin::{BurnIn, BurnInConfig, Idle},
+        control::{PlayerControl, Scaled, Sensitivity, WithButton},
+        input::{Input, KeyConfig},
+        menu::MenuEvent,
+    },
+    embedded_graphics::draw_target::DrawTarget,
     embedded_hal::{
         digital::v2::{InputPin, OutputPin},
         timer::CountDown,
     },
-    nb::block,
     panic_halt as _,
-    ssd1306::{prelude::SPIInterface, size::DisplaySize128x64},
+    pong::{ai::Ai, Event, Orientation, Overlay, Screen, Side, Status},
+    settings::{Settings, PLAY},
+    ssd1306::prelude::DisplayRotation,
     stm32::{blink_loop, prelude::*},
 };
 
 mod blink;
-mod control;
 mod drawer;
+mod layout;
 mod rnd;
+mod settings;
+mod skin;
 mod stm32;
 
+#[cfg(not(any(
+    feature = "display-128x32",
+    feature = "display-96x16",
+    feature = "display-64x48",
+    feature = "st7735",
+)))]
+use ssd1306::size::DisplaySize128x64 as DisplaySize;
+
+#[cfg(feature = "display-128x32")]
+use ssd1306::size::DisplaySize128x32 as DisplaySize;
+
+#[cfg(feature = "display-96x16")]
+use ssd1306::size::DisplaySize96x16 as DisplaySize;
+
+#[cfg(feature = "display-64x48")]
+use ssd1306::size::DisplaySize64x48 as DisplaySize;
+
+#[cfg(not(any(feature = "rotate90", feature = "rotate270")))]
+const ROTATION: (DisplayRotation, Orientation) = (DisplayRotation::Rotate0, Orientation::Landscape);
+
+#[cfg(feature = "rotate90")]
+const ROTATION: (DisplayRotation, Orientation) = (DisplayRotation::Rotate90, Orientation::Portrait);
+
+#[cfg(feature = "rotate270")]
+const ROTATION: (DisplayRotation, Orientation) =
+    (DisplayRotation::Rotate270, Orientation::Portrait);
+
+const FRAME_RATE: u32 = 60;
+
+const BURN_IN: BurnInConfig = BurnInConfig::with_frame_rate(FRAME_RATE);
+
+const KEY: KeyConfig = KeyConfig::with_frame_rate(FRAME_RATE);
+
+/// Encoder steps that count as a press on screens waiting for one
+const WAKE_MOTION: i32 = 3;
+
+/// Fewer counts turned during calibration keep the previous scale
+const CALIBRATION_MIN_COUNTS: i32 = 8;
+
+/// Frames the buzzer sounds when something changes in the menu
+const CLICK_FRAMES: u32 = 2;
+
+#[cfg(all(feature = "st7735", not(feature = "colors-neon")))]
+const COLORS: drawer::Colors<embedded_graphics::pixelcolor::Rgb565> = drawer::Colors::TABLE;
+
+#[cfg(all(feature = "st7735", feature = "colors-neon"))]
+const COLORS: drawer::Colors<embedded_graphics::pixelcolor::Rgb565> = drawer::Colors::NEON;
+
+#[cfg(not(feature = "arcade"))]
+const THEME: drawer::Theme = drawer::Theme::Modern;
+
+#[cfg(feature = "arcade")]
+const THEME: drawer::Theme = drawer::Theme::Arcade {
+    borders: cfg!(feature = "arcade-borders"),
+};
+
+#[cfg(feature = "forces")]
+const ENVIRONMENT: pong::environment::Environment = pong::environment::Environment {
+    gravity: 0.0002,
+    wind: 0.0001,
+    wind_period: 300,
+    zones: [
+        Some(pong::environment::Zone {
+            x: 0.4,
+            y: 0.0,
+            width: 0.2,
+            height: 1.0,
+            factor: 0.99,
+        }),
+        None,
+        None,
+        None,
+    ],
+};
+
 #[entry]
 fn main() -> ! {
     let stm32::Device {
+        #[cfg(not(feature = "i2c"))]
         spi,
+        #[cfg(feature = "i2c")]
+        i2c,
         key,
         mut led,
+        #[cfg(not(feature = "i2c"))]
         dc,
+        #[cfg(not(feature = "i2c"))]
         cs,
         rand_seed,
-        encoder1,
-        encoder2,
+        player1,
+        player2,
+        button1,
+        button2,
+        buzzer,
         syst,
+        mut storage,
+        #[cfg(feature = "st7735")]
+        rst,
+        #[cfg(feature = "dma")]
+        dma,
+        ..
     } = stm32::Device::new();
 
     OutputPin::set_high(&mut led).unwrap();
 
-    let interface = SPIInterface::new(spi, dc, cs);
+    let (rotation, orientation) = ROTATION;
+
+    #[cfg(not(any(feature = "dma", feature = "st7735")))]
+    let mut drawer = {
+        #[cfg(not(feature = "i2c"))]
+        let interface = ssd1306::prelude::SPIInterface::new(spi, dc, cs);
+
+        #[cfg(feature = "i2c")]
+        let interface = ssd1306::I2CDisplayInterface::new(i2c);
+
+        drawer::with_ssd1306(interface, DisplaySize, rotation)
+    };
+
+    #[cfg(feature = "st7735")]
+    let mut drawer = {
+        let mut cs = cs;
+        OutputPin::set_low(&mut cs).unwrap();
+
+        use drivers::indexed::Frame;
+        let frame = cortex_m::singleton!(: Frame = Frame::new()).unwrap();
+        let mut drawer = drawer::with_st7735(spi, dc, rst, &mut stm32::AsmDelay, frame, rotation);
+        drawer.set_colors(COLORS);
+        drawer
+    };
+
+    #[cfg(feature = "dma")]
+    let mut drawer = {
+        let interface = ssd1306::prelude::SPIInterface::new(spi, dc, cs);
+        let (spi, dc, mut cs) = drawer::init_ssd1306(interface, DisplaySize, rotation).release();
+
+        // the display is the only device on the bus
+        OutputPin::set_low(&mut cs).unwrap();
+
+        let front = cortex_m::singleton!(: [u8; 1024] = [0; 1024]).unwrap();
+        let back = cortex_m::singleton!(: [u8; 1024] = [0; 1024]).unwrap();
+        let writer = stm32::DisplayDma::new(spi, dc, dma, front);
+        drawer::with_dma(writer, back, DisplaySize, rotation)
+    };
+
+    let (width, height) = match orientation {
+        Orientation::Landscape => (pong::FIELD_WIDTH, pong::FIELD_HEIGHT),
+        Orientation::Portrait => (pong::FIELD_HEIGHT, pong::FIELD_WIDTH),
+    };
+
+    let mut timer = syst.start_count_down(FRAME_RATE.hz());
+    drawer.set_theme(THEME);
+
+    #[cfg(feature = "skin-classic")]
+    drawer.set_skin(skin::Skin::classic());
+
+    let mut settings = Settings::load(&mut storage);
+    let [sensitivity1, sensitivity2] = settings.sensitivity;
+    let mut input = Input::new(
+        Scaled::new(WithButton::new(player1, button1), sensitivity1),
+        Scaled::new(WithButton::new(player2, button2), sensitivity2),
+        key,
+        KEY,
+    );
+
+    // holding the key at power on opens the calibration
+    for _ in 0..KEY.debounce {
+        wait_frame(&mut timer, &mut input);
+        input.sample().unwrap();
+    }
+    if input.key_down() {
+        calibrate(&mut input, &mut timer, &mut drawer, &mut settings);
+        settings.save(&mut storage).unwrap();
+    }
 
-    let mut drawer = drawer::Ssd1306PongDrawer::new(interface, DisplaySize128x64);
+    let mut burn_in = BurnIn::new(BURN_IN);
+    let mut beeper = Beeper::new(buzzer);
+
+    wait_press(
+        &mut input,
+        &mut timer,
+        &mut led,
+        &mut drawer,
+        &mut burn_in,
+        &mut beeper,
+        &Screen::Splash,
+    );
+
+    if settings_menu(
+        &mut input,
+        &mut timer,
+        &mut drawer,
+        &mut burn_in,
+        &mut beeper,
+        &mut settings,
+    ) {
+        settings.save(&mut storage).unwrap();
+    }
 
-    let mut timer = syst.start_count_down(60.hz());
     let mut rand_generator = rnd::PseudoRandomGenerator::new(rand_seed);
-    let mut pong = pong::Pong::new(128, 64, || rand_generator.get() as i32);
+    let mut pong =
+        pong::Pong::with_orientation(width, height, orientation, || rand_generator.get() as i32);
+    drawer.fit_field(pong.size());
+
+    #[cfg(feature = "forces")]
+    pong.set_environment(ENVIRONMENT);
 
-    let mut player1 = PlayerEncoder::new(encoder1);
-    let mut player2 = PlayerEncoder::new(encoder2);
+    #[cfg(feature = "balance")]
+    pong.set_balance(pong::Balance::Auto);
 
-    let mut score = (0, 0);
+    let (mut ai1, mut ai2) =
+        apply_settings(&settings, &mut pong, &mut drawer, &mut burn_in, &mut beeper);
+
+    let mut paused = None;
+
+    #[cfg(feature = "effects")]
+    let mut last_progress = None;
+
+    // set when drawing a frame took longer than the frame period, the next
+    // frame is then simulated right away and not drawn to keep the game speed
+    let mut behind = false;
 
     loop {
-        block!(timer.wait()).unwrap();
+        let catch_up = behind;
+        behind = false;
+        if !catch_up {
+            wait_frame(&mut timer, &mut input);
+        }
+
+        let active = input.sample().unwrap();
+        let pressed = input.pressed();
+        let pause = input.pause_pressed();
+        let (mut delta1, mut delta2) = input.take_motion();
+        beeper.update().unwrap();
 
-        let delta1 = player1.delta();
-        let delta2 = player2.delta();
+        burn_in.next(active);
+        drawer.protect(&burn_in);
+        if burn_in.idle() == Idle::Screensaver {
+            drawer.draw_screensaver(&burn_in);
+            continue;
+        }
+
+        if let Some(progress) = paused {
+            if pressed || pause {
+                paused = None;
+            } else {
+                pong::render_overlay(&progress, &Overlay::Paused, &mut drawer);
+            }
+            continue;
+        }
+
+        // computer players replace the controls of their side
+        if let Some(ai) = &mut ai1 {
+            delta1 = ai.next(&mut pong);
+        }
+        if let Some(ai) = &mut ai2 {
+            delta2 = ai.next(&mut pong);
+        }
+
+        // the shared key serves for both, a button only on its own side
+        let serving = match pong.status() {
+            Status::Serve(server) => Some(server),
+            _ => None,
+        };
+        let server_pressed = match serving {
+            Some(Side::Player1) => input.player_pressed().0,
+            Some(Side::Player2) => input.player_pressed().1,
+            None => false,
+        };
+        if serving.is_some() && (input.key_pressed() || server_pressed) {
+            pong.serve();
+        }
 
         let res = pong.next(delta1, delta2);
+        if let Some(event) = pong.event() {
+            beeper.beep(beep_frames(event));
+        }
 
         match res {
             pong::Result::GameInProgress(progress) => {
-                use pong::Drawer;
-
-                drawer.clear();
-                drawer.draw_score(&score);
-                drawer.draw_ball(&progress.ball);
-                drawer.draw_player(&progress.player1);
-                drawer.draw_player(&progress.player2);
-                drawer.flush();
+                if (pressed && serving.is_none()) || pause {
+                    paused = Some(progress);
+                }
+
+                #[cfg(feature = "effects")]
+                {
+                    drawer.update_effects(&progress, pong.event());
+                    last_progress = Some(progress);
+                }
+
+                if catch_up {
+                    continue;
+                }
+
+                match pong.serve_countdown() {
+                    Some(frames) => {
+                        let seconds = (frames + FRAME_RATE - 1) / FRAME_RATE;
+                        let overlay = Overlay::Countdown(seconds);
+                        pong::render_overlay(&progress, &overlay, &mut drawer);
+                    }
+                    None => pong::render(&progress, &mut drawer),
+                }
+                behind = timer.wait().is_ok();
             }
             pong::Result::GameOver(last_goal_from) => {
-                match last_goal_from {
-                    pong::LastGoalFrom::Player1 => score.0 += 1,
-                    pong::LastGoalFrom::Player2 => score.1 += 1,
+                #[cfg(feature = "effects")]
+                if let Some(progress) = last_progress.take() {
+                    drawer.update_effects(&progress, pong.event());
+                    while drawer.effects_running() {
+                        pong::render(&progress, &mut drawer);
+                        wait_frame(&mut timer, &mut input);
+                        input.sample().unwrap();
+                        input.take_motion();
+                        beeper.update().unwrap();
+                        drawer.update_effects(&progress, None);
+                    }
+                    drawer.clear_effects();
+                }
+
+                let screen = match pong.status() {
+                    Status::MatchOver(winner) => Screen::MatchOver(winner, pong.score()),
+                    _ => Screen::Goal(last_goal_from.into(), pong.score()),
                 };
-                wait_press(&mut player1, &mut player2, &key, &mut timer, &mut led);
+                wait_press(
+                    &mut input,
+                    &mut timer,
+                    &mut led,
+                    &mut drawer,
+                    &mut burn_in,
+                    &mut beeper,
+                    &screen,
+                );
+
+                if let Screen::MatchOver(..) = screen {
+                    if settings_menu(
+                        &mut input,
+                        &mut timer,
+                        &mut drawer,
+                        &mut burn_in,
+                        &mut beeper,
+                        &mut settings,
+                    ) {
+                        settings.save(&mut storage).unwrap();
+                    }
+                    let ai = apply_settings(
+                        &settings,
+                        &mut pong,
+                        &mut drawer,
+                        &mut burn_in,
+                        &mut beeper,
+                    );
+                    ai1 = ai.0;
+                    ai2 = ai.1;
+                }
                 pong.reinit();
             }
             _ => {
-                wait_press(&mut player1, &mut player2, &key, &mut timer, &mut led);
+                wait_press(
+                    &mut input,
+                    &mut timer,
+                    &mut led,
+                    &mut drawer,
+                    &mut burn_in,
+                    &mut beeper,
+                    &Screen::Splash,
+                );
                 pong.reinit();
             }
         };
     }
 }
 
-fn wait_press<Player1, Player2, Key, Timer, Led>(
-    player1: &mut Player1,
-    player2: &mut Player2,
-    key: &Key,
+/// Shows `screen` until a player presses the key or turns a control, a press
+/// while the screensaver runs only wakes the display up
+fn wait_press<Player1, Player2, Key, Timer, Led, Buzzer, Target>(
+    input: &mut Input<Player1, Player2, Key>,
     timer: &mut Timer,
     led: &mut Led,
+    drawer: &mut drawer::PongDrawer<Target>,
+    burn_in: &mut BurnIn,
+    beeper: &mut Beeper<Buzzer>,
+    screen: &Screen,
 ) where
     Player1: PlayerControl,
     Player2: PlayerControl,
     Key: InputPin,
     Timer: CountDown,
     Led: OutputPin,
+    Buzzer: OutputPin,
     Key::Error: core::fmt::Debug,
     Led::Error: core::fmt::Debug,
+    Buzzer::Error: core::fmt::Debug,
+    Target: DrawTarget + drawer::Flush + drawer::BurnInTarget,
+    Target::Color: drawer::Palette,
+    Target::Error: core::fmt::Debug,
 {
     led.set_low().unwrap();
-    control::wait_press(player1, player2, key, timer);
+
+    loop {
+        wait_frame(timer, input);
+
+        let saver = burn_in.idle() == Idle::Screensaver;
+        let active = input.sample().unwrap();
+        beeper.update().unwrap();
+        burn_in.next(active);
+        drawer.protect(burn_in);
+
+        if burn_in.idle() == Idle::Screensaver {
+            drawer.draw_screensaver(burn_in);
+        } else {
+            pong::render_screen(screen, drawer);
+        }
+
+        let (motion1, motion2) = input.motion();
+        let turned = motion1.abs() >= WAKE_MOTION || motion2.abs() >= WAKE_MOTION;
+        if turned {
+            input.take_motion();
+        }
+
+        if (input.pressed() || turned) && !saver {
+            break;
+        }
+    }
+
+    input.take_motion();
     led.set_high().unwrap();
 }
 
+/// Shows the settings until a player picks PLAY, the motion of both controls
+/// moves through the menu. The contrast and sound change right away so they
+/// can be tried out, the controls get their sensitivity once the menu is
+/// left. Returns whether any setting changed
+fn settings_menu<Control1, Control2, Key, Timer, Buzzer, Target>(
+    input: &mut Input<Scaled<Control1>, Scaled<Control2>, Key>,
+    timer: &mut Timer,
+    drawer: &mut drawer::PongDrawer<Target>,
+    burn_in: &mut BurnIn,
+    beeper: &mut Beeper<Buzzer>,
+    settings: &mut Settings,
+) -> bool
+where
+    Control1: PlayerControl,
+    Control2: PlayerControl,
+    Key: InputPin,
+    Timer: CountDown,
+    Buzzer: OutputPin,
+    Key::Error: core::fmt::Debug,
+    Buzzer::Error: core::fmt::Debug,
+    Target: DrawTarget + drawer::Flush + drawer::BurnInTarget,
+    Target::Color: drawer::Palette,
+    Target::Error: core::fmt::Debug,
+{
+    let mut menu = settings.menu();
+    input.take_motion();
+
+    let chosen = loop {
+        wait_frame(timer, input);
+
+        let saver = burn_in.idle() == Idle::Screensaver;
+        let active = input.sample().unwrap();
+        let (motion1, motion2) = input.take_motion();
+        beeper.update().unwrap();
+        burn_in.next(active);
+        drawer.protect(burn_in);
+
+        // a press that wakes the display up changes nothing
+        if saver || burn_in.idle() == Idle::Screensaver {
+            drawer.draw_screensaver(burn_in);
+            continue;
+        }
+
+        let event = menu.update(motion1 + motion2, input.pressed());
+
+        let mut chosen = *settings;
+        chosen.apply(&menu);
+        burn_in.set_config(BurnInConfig {
+            contrast: chosen.contrast,
+            ..BURN_IN
+        });
+        beeper.set_enabled(chosen.sound);
+        if event.is_some() {
+            beeper.beep(CLICK_FRAMES);
+        }
+
+        if event == Some(MenuEvent::Activated(PLAY)) {
+            break chosen;
+        }
+        drawer.draw_menu(&menu);
+    };
+
+    input.take_motion();
+    let (player1, player2) = input.players();
+    player1.set_sensitivity(chosen.sensitivity[0]);
+    player2.set_sensitivity(chosen.sensitivity[1]);
+
+    let changed = chosen != *settings;
+    *settings = chosen;
+    changed
+}
+
+/// Hands the settings to the game, the drawer, the display and the buzzer,
+/// returns the computer players of both sides
+fn apply_settings<RND, Buzzer, Target>(
+    settings: &Settings,
+    pong: &mut pong::Pong<RND>,
+    drawer: &mut drawer::PongDrawer<Target>,
+    burn_in: &mut BurnIn,
+    beeper: &mut Beeper<Buzzer>,
+) -> (Option<Ai>, Option<Ai>)
+where
+    RND: FnMut() -> i32,
+    Buzzer: OutputPin,
+    Target: DrawTarget,
+    Target::Color: drawer::Palette,
+    Target::Error: core::fmt::Debug,
+{
+    pong.set_rules(settings.rules());
+    drawer.set_names(settings.names);
+    burn_in.set_config(BurnInConfig {
+        contrast: settings.contrast,
+        ..BURN_IN
+    });
+    beeper.set_enabled(settings.sound);
+    settings.ai()
+}
+
+/// Frames the buzzer sounds for an event of the game
+fn beep_frames(event: Event) -> u32 {
+    match event {
+        Event::PaddleHit(..) => 3,
+        Event::BorderHit => 1,
+        Event::Goal(_) => 20,
+    }
+}
+
+/// Asks both players to turn their control as far as it should move the
+/// paddle from the bottom of the field to the top and scales the controls
+/// to that
+fn calibrate<Control1, Control2, Key, Timer, Target>(
+    input: &mut Input<Scaled<Control1>, Scaled<Control2>, Key>,
+    timer: &mut Timer,
+    drawer: &mut drawer::PongDrawer<Target>,
+    settings: &mut Settings,
+) where
+    Control1: PlayerControl,
+    Control2: PlayerControl,
+    Key: InputPin,
+    Timer: CountDown,
+    Key::Error: core::fmt::Debug,
+    Target: DrawTarget + drawer::Flush,
+    Target::Color: drawer::Palette,
+    Target::Error: core::fmt::Debug,
+{
+    let (player1, player2) = input.players();
+    player1.set_sensitivity(Sensitivity::RAW);
+    player2.set_sensitivity(Sensitivity::RAW);
+
+    for (index, &side) in [Side::Player1, Side::Player2].iter().enumerate() {
+        input.take_motion();
+
+        let counts = loop {
+            wait_frame(timer, input);
+            input.sample().unwrap();
+
+            let (motion1, motion2) = input.motion();
+            let counts = match side {
+                Side::Player1 => motion1,
+                Side::Player2 => motion2,
+            };
+            drawer.draw_calibration(side, counts);
+
+            let (pressed1, pressed2) = input.player_pressed();
+            let confirmed = match side {
+                Side::Player1 => pressed1,
+                Side::Player2 => pressed2,
+            };
+            if input.key_pressed() || confirmed {
+                break counts;
+            }
+        };
+
+        if counts.abs() >= CALIBRATION_MIN_COUNTS {
+            let steps = pong::paddle_travel_steps(settings.paddle_size as u32);
+            settings.sensitivity[index] = settings.sensitivity[index].calibrated(counts, steps);
+        }
+    }
+
+    input.take_motion();
+    let (player1, player2) = input.players();
+    player1.set_sensitivity(settings.sensitivity[0]);
+    player2.set_sensitivity(settings.sensitivity[1]);
+}
+
+/// Waits for the next frame, controls decoded in software are polled in the
+/// meantime
+fn wait_frame<Player1, Player2, Key, Timer>(
+    timer: &mut Timer,
+    input: &mut Input<Player1, Player2, Key>,
+) where
+    Player1: PlayerControl,
+    Player2: PlayerControl,
+    Key: InputPin,
+    Timer: CountDown,
+{
+    while timer.wait().is_err() {
+        input.poll();
+    }
+}
+
 #[exception]
 unsafe fn HardFault(_ef: &ExceptionFrame) -> ! {
     blink_loop(20);
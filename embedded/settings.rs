@@ -0,0 +1,269 @@
+use {
+    core::ops::RangeInclusive,
+    drivers::{
+        control::Sensitivity,
+        menu::{Entry, Item, Menu, TEXT_LEN},
+        storage::{self, Storage},
+    },
+    pong::{
+        ai::{Ai, Difficulty},
+        Rules, Side,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    TwoPlayers,
+    /// The computer plays player 2
+    Single,
+    /// The computer plays both sides
+    Demo,
+}
+
+impl GameMode {
+    pub const ALL: [Self; 3] = [Self::TwoPlayers, Self::Single, Self::Demo];
+}
+
+/// Everything kept over power cycles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub sensitivity: [Sensitivity; 2],
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub win_score: u8,
+    /// Percent of the normal speed
+    pub ball_speed: u8,
+    /// Percent of the normal height
+    pub paddle_size: u8,
+    pub contrast: u8,
+    pub sound: bool,
+    /// Padded with spaces, blank names are shown as "PLAYER 1" and "PLAYER 2"
+    pub names: [[u8; TEXT_LEN]; 2],
+}
+
+const SIZE: usize = SENSITIVITIES_SIZE + 7 + 2 * TEXT_LEN;
+
+/// The sensitivities come first, they were all there was before the menu
+const SENSITIVITIES_SIZE: usize = 2 * Sensitivity::SIZE;
+
+/// Limits of the spinners, stored values outside them are not taken
+const WIN_SCORES: RangeInclusive<u8> = 1..=21;
+const PERCENTS: RangeInclusive<u8> = 50..=200;
+const ACCELERATIONS: RangeInclusive<u8> = 0..=50;
+const DEAD_ZONES: RangeInclusive<u8> = 0..=5;
+
+const MODES: &[&str] = &["2 PLAYERS", "VS CPU", "DEMO"];
+const LEVELS: &[&str] = &["EASY", "NORMAL", "HARD"];
+
+/// Positions of the entries in `Settings::menu`
+pub const PLAY: usize = 0;
+const MODE: usize = 1;
+const LEVEL: usize = 2;
+const WIN_SCORE: usize = 3;
+const BALL_SPEED: usize = 4;
+const PADDLE_SIZE: usize = 5;
+const CONTRAST: usize = 6;
+const SOUND: usize = CONTRAST + HAS_CONTRAST as usize;
+const NAME1: usize = SOUND + 1;
+const ACCELERATION1: usize = SOUND + 2;
+const DEAD_ZONE1: usize = SOUND + 3;
+const NAME2: usize = SOUND + 4;
+const ACCELERATION2: usize = SOUND + 5;
+const DEAD_ZONE2: usize = SOUND + 6;
+pub const ENTRIES: usize = DEAD_ZONE2 + 1;
+
+/// The ST7735 has no contrast to set, the entries after it move up
+const HAS_CONTRAST: bool = cfg!(not(feature = "st7735"));
+
+impl Settings {
+    pub const DEFAULT: Self = Self {
+        sensitivity: [Sensitivity::RAW; 2],
+        mode: GameMode::TwoPlayers,
+        difficulty: Difficulty::Normal,
+        win_score: 11,
+        ball_speed: 100,
+        paddle_size: 100,
+        contrast: 0xcf,
+        sound: true,
+        names: [[b' '; TEXT_LEN]; 2],
+    };
+
+    /// Stored settings, the defaults if nothing valid is stored
+    pub fn load<S: Storage>(storage: &mut S) -> Self {
+        let mut bytes = [0; SIZE];
+        let size = if matches!(storage::load(storage, &mut bytes), Ok(true)) {
+            SIZE
+        } else if matches!(
+            storage::load(storage, &mut bytes[..SENSITIVITIES_SIZE]),
+            Ok(true)
+        ) {
+            SENSITIVITIES_SIZE
+        } else {
+            return Self::DEFAULT;
+        };
+
+        let mut settings = Self::DEFAULT;
+        let (sensitivities, rest) = bytes.split_at(SENSITIVITIES_SIZE);
+        for (sensitivity, bytes) in settings
+            .sensitivity
+            .iter_mut()
+            .zip(sensitivities.chunks_exact(Sensitivity::SIZE))
+        {
+            let mut field = [0; Sensitivity::SIZE];
+            field.copy_from_slice(bytes);
+            *sensitivity = Sensitivity::from_bytes(field);
+        }
+        if size == SENSITIVITIES_SIZE {
+            return settings;
+        }
+
+        let (values, names) = rest.split_at(7);
+        if let Some(&mode) = GameMode::ALL.get(values[0] as usize) {
+            settings.mode = mode;
+        }
+        if let Some(&difficulty) = Difficulty::ALL.get(values[1] as usize) {
+            settings.difficulty = difficulty;
+        }
+        if WIN_SCORES.contains(&values[2]) {
+            settings.win_score = values[2];
+        }
+        if PERCENTS.contains(&values[3]) {
+            settings.ball_speed = values[3];
+        }
+        if PERCENTS.contains(&values[4]) {
+            settings.paddle_size = values[4];
+        }
+        settings.contrast = values[5];
+        settings.sound = values[6] != 0;
+        for (name, bytes) in settings.names.iter_mut().zip(names.chunks_exact(TEXT_LEN)) {
+            name.copy_from_slice(bytes);
+        }
+        settings
+    }
+
+    pub fn save<S: Storage>(&self, storage: &mut S) -> Result<(), S::Error> {
+        let mut bytes = [0; SIZE];
+        let (sensitivities, rest) = bytes.split_at_mut(SENSITIVITIES_SIZE);
+        for (sensitivity, bytes) in self
+            .sensitivity
+            .iter()
+            .zip(sensitivities.chunks_exact_mut(Sensitivity::SIZE))
+        {
+            bytes.copy_from_slice(&sensitivity.to_bytes());
+        }
+
+        let (values, names) = rest.split_at_mut(7);
+        values.copy_from_slice(&[
+            self.mode as u8,
+            self.difficulty as u8,
+            self.win_score,
+            self.ball_speed,
+            self.paddle_size,
+            self.contrast,
+            self.sound as u8,
+        ]);
+        for (name, bytes) in self.names.iter().zip(names.chunks_exact_mut(TEXT_LEN)) {
+            bytes.copy_from_slice(name);
+        }
+        storage::save(storage, &bytes)
+    }
+
+    pub fn rules(&self) -> Rules {
+        Rules {
+            win_score: self.win_score as u32,
+            ball_speed: self.ball_speed as u32,
+            paddle_size: self.paddle_size as u32,
+            ..Rules::default()
+        }
+    }
+
+    /// Computer players of both sides
+    pub fn ai(&self) -> (Option<Ai>, Option<Ai>) {
+        let player1 = Ai::new(Side::Player1, self.difficulty);
+        let player2 = Ai::new(Side::Player2, self.difficulty);
+        match self.mode {
+            GameMode::TwoPlayers => (None, None),
+            GameMode::Single => (None, Some(player2)),
+            GameMode::Demo => (Some(player1), Some(player2)),
+        }
+    }
+
+    /// Menu with an entry for every setting that can be changed on the
+    /// device, and one to start the game
+    pub fn menu(&self) -> Menu<ENTRIES> {
+        let spinner = |value: u8, range: RangeInclusive<u8>, step| Item::Spinner {
+            value: value as i32,
+            min: *range.start() as i32,
+            max: *range.end() as i32,
+            step,
+        };
+        let entry = |label, item| Entry { label, item };
+        let acceleration = |index: usize| self.sensitivity[index].acceleration;
+        let dead_zone = |index: usize| self.sensitivity[index].dead_zone;
+
+        let all = [
+            entry("PLAY", Item::Action),
+            entry(
+                "MODE",
+                Item::List {
+                    options: MODES,
+                    index: self.mode as usize,
+                },
+            ),
+            entry(
+                "LEVEL",
+                Item::List {
+                    options: LEVELS,
+                    index: self.difficulty as usize,
+                },
+            ),
+            entry("WIN SCORE", spinner(self.win_score, WIN_SCORES, 1)),
+            entry("BALL SPEED", spinner(self.ball_speed, PERCENTS, 10)),
+            entry("PADDLE", spinner(self.paddle_size, PERCENTS, 10)),
+            entry("CONTRAST", spinner(self.contrast, 0..=255, 16)),
+            entry("SOUND", Item::Toggle(self.sound)),
+            entry("PLAYER 1", Item::Text(self.names[0])),
+            entry("ACCEL 1", spinner(acceleration(0), ACCELERATIONS, 5)),
+            entry("DEADZONE 1", spinner(dead_zone(0), DEAD_ZONES, 1)),
+            entry("PLAYER 2", Item::Text(self.names[1])),
+            entry("ACCEL 2", spinner(acceleration(1), ACCELERATIONS, 5)),
+            entry("DEADZONE 2", spinner(dead_zone(1), DEAD_ZONES, 1)),
+        ];
+
+        let mut entries = [all[PLAY]; ENTRIES];
+        entries[..CONTRAST].copy_from_slice(&all[..CONTRAST]);
+        entries[CONTRAST..].copy_from_slice(&all[all.len() - (ENTRIES - CONTRAST)..]);
+        Menu::new(entries)
+    }
+
+    /// Takes the values of a menu made by `menu`
+    pub fn apply(&mut self, menu: &Menu<ENTRIES>) {
+        let index = |entry| match menu.item(entry) {
+            Item::List { index, .. } => index,
+            _ => 0,
+        };
+        let value = |entry| match menu.item(entry) {
+            Item::Spinner { value, .. } => value.max(0).min(u8::MAX as i32) as u8,
+            _ => 0,
+        };
+        let text = |entry| match menu.item(entry) {
+            Item::Text(text) => text,
+            _ => [b' '; TEXT_LEN],
+        };
+
+        self.mode = GameMode::ALL[index(MODE)];
+        self.difficulty = Difficulty::ALL[index(LEVEL)];
+        self.win_score = value(WIN_SCORE);
+        self.ball_speed = value(BALL_SPEED);
+        self.paddle_size = value(PADDLE_SIZE);
+        if HAS_CONTRAST {
+            self.contrast = value(CONTRAST);
+        }
+        self.sound = matches!(menu.item(SOUND), Item::Toggle(true));
+        self.names = [text(NAME1), text(NAME2)];
+        self.sensitivity[0].acceleration = value(ACCELERATION1);
+        self.sensitivity[0].dead_zone = value(DEAD_ZONE1);
+        self.sensitivity[1].acceleration = value(ACCELERATION2);
+        self.sensitivity[1].dead_zone = value(DEAD_ZONE2);
+    }
+}
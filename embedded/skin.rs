@@ -0,0 +1,81 @@
+use {
+    embedded_graphics::{pixelcolor::BinaryColor, prelude::*},
+    tinybmp::Bmp,
+};
+
+/// 1-bpp sprites drawn instead of primitives. A sprite is only used when its
+/// size matches the size of the shape on the screen, so one skin is made for
+/// one display size
+pub struct Skin {
+    pub ball: Option<Bmp<'static, BinaryColor>>,
+    pub paddle: Option<Bmp<'static, BinaryColor>>,
+    /// Digits 0 to 9 side by side, all of the same width
+    pub digits: Option<Bmp<'static, BinaryColor>>,
+}
+
+fn load(data: &'static [u8]) -> Option<Bmp<'static, BinaryColor>> {
+    Bmp::from_slice(data).ok()
+}
+
+impl Skin {
+    /// Primitives only
+    pub const NONE: Self = Self {
+        ball: None,
+        paddle: None,
+        digits: None,
+    };
+
+    /// Ring ball, notched paddles and pixel font digits for 128x64 displays
+    pub fn classic() -> Self {
+        Self {
+            ball: load(include_bytes!("skins/classic/ball.bmp")),
+            paddle: load(include_bytes!("skins/classic/paddle.bmp")),
+            digits: load(include_bytes!("skins/classic/digits.bmp")),
+        }
+    }
+
+    /// Size of one digit
+    pub fn digit_size(&self) -> Option<Size> {
+        self.digits
+            .as_ref()
+            .map(|digits| Size::new(digits.size().width / 10, digits.size().height))
+    }
+}
+
+/// Lit pixels of `sprite` if it has the given size, placed at `top_left`
+pub fn sprite_pixels<'a>(
+    sprite: &'a Option<Bmp<'static, BinaryColor>>,
+    size: Size,
+    top_left: Point,
+) -> Option<impl Iterator<Item = Point> + 'a> {
+    let sprite = sprite.as_ref().filter(|sprite| sprite.size() == size)?;
+    Some(
+        sprite
+            .pixels()
+            .filter(|Pixel(_, color)| color.is_on())
+            .map(move |Pixel(point, _)| point + top_left),
+    )
+}
+
+/// Lit pixels of a digit of `digits` if the digits have the given size
+pub fn digit_pixels<'a>(
+    skin: &'a Skin,
+    digit: u32,
+    size: Size,
+    top_left: Point,
+) -> Option<impl Iterator<Item = Point> + 'a> {
+    if skin.digit_size() != Some(size) {
+        return None;
+    }
+
+    let digits = skin.digits.as_ref()?;
+    let left = (digit % 10 * size.width) as i32;
+    let right = left + size.width as i32;
+
+    Some(
+        digits
+            .pixels()
+            .filter(move |Pixel(point, color)| color.is_on() && point.x >= left && point.x < right)
+            .map(move |Pixel(point, _)| point - Point::new(left, 0) + top_left),
+    )
+}
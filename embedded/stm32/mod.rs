@@ -9,3 +9,24 @@ mod stm32f103;
 
 #[cfg(feature = "stm32f103")]
 pub use stm32f103::*;
+
+/// Codes of the IR receiver, both players can use one remote each
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+pub static REMOTE_CODES: drivers::nec::RemoteCodes = drivers::nec::RemoteCodes::new();
+
+/// Counts of the encoders decoded in pin interrupts
+#[cfg(feature = "soft-encoders")]
+pub static ENCODER_COUNTS: [drivers::quadrature::EncoderCount; 2] = [
+    drivers::quadrature::EncoderCount::new(),
+    drivers::quadrature::EncoderCount::new(),
+];
+
+/// Busy waiting delay for display initialization, the system timer is
+/// taken by the frame timer
+pub struct AsmDelay;
+
+impl embedded_hal::blocking::delay::DelayMs<u8> for AsmDelay {
+    fn delay_ms(&mut self, ms: u8) {
+        cortex_m::asm::delay(SYSCLK_HZ / 1000 * ms as u32);
+    }
+}
@@ -1,13 +1,18 @@
 use {
     crate::blink::Blink,
     cortex_m::peripheral::SYST,
+    drivers::{
+        control::PlayerEncoder,
+        storage::{Storage, RECORD_SIZE},
+    },
     embedded_hal::spi::MODE_0,
     stm32f1xx_hal::{
         adc::Adc,
         delay::Delay,
+        flash::{self, FlashSize, SectorSize},
         gpio::{
-            gpioa::{PA0, PA1, PA2, PA3, PA5, PA7, PA8, PA9},
-            gpiob::PB5,
+            gpioa::{PA0, PA1, PA11, PA2, PA3, PA5, PA7, PA8, PA9},
+            gpiob::{PB5, PB8, PB9},
             gpioc::PC13,
             Alternate, Floating, Input, Output, PullUp, PushPull,
         },
@@ -19,19 +24,368 @@ use {
     },
 };
 
+#[cfg(feature = "st7735")]
+use stm32f1xx_hal::gpio::gpiob::PB0;
+
+#[cfg(feature = "dma")]
+use {
+    drivers::dma::WriteDma,
+    embedded_hal::digital::v2::OutputPin,
+    stm32f1xx_hal::{
+        dma::{dma1, Transfer, R},
+        spi::SpiTxDma,
+    },
+};
+
 pub use stm32f1xx_hal::prelude;
 
+pub const SYSCLK_HZ: u32 = 72_000_000;
+
+type SpiPins = (PA5<Alternate<PushPull>>, NoMiso, PA7<Alternate<PushPull>>);
+
+#[cfg(not(any(
+    feature = "player1-pot",
+    feature = "player1-buttons",
+    feature = "player1-nunchuk",
+    feature = "player1-remote",
+    feature = "soft-encoders",
+)))]
+pub type Player1 =
+    PlayerEncoder<qei::Qei<TIM1, Tim1NoRemap, (PA8<Input<Floating>>, PA9<Input<Floating>>)>>;
+
+/// Encoder on PA8 and PA9 decoded in a pin interrupt
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+pub type Player1 = InterruptEncoder<'static>;
+
+/// Potentiometer on PA6
+#[cfg(feature = "player1-pot")]
+pub type Player1 = Potentiometer<SharedAdc<'static, Adc<ADC1>>, ADC1, PA6<Analog>>;
+
+/// Up on PB12, down on PB13
+#[cfg(feature = "player1-buttons")]
+pub type Player1 = Buttons<PB12<Input<PullUp>>, PB13<Input<PullUp>>>;
+
+#[cfg(feature = "player1-nunchuk")]
+pub type Player1 = NunchukPort;
+
+/// Left buttons of a remote seen by the IR receiver
+#[cfg(feature = "player1-remote")]
+pub type Player1 = RemotePlayer<'static>;
+
+#[cfg(not(any(
+    feature = "player2-pot",
+    feature = "player2-buttons",
+    feature = "player2-nunchuk",
+    feature = "player2-remote",
+    feature = "soft-encoders",
+)))]
+pub type Player2 =
+    PlayerEncoder<qei::Qei<TIM2, Tim2NoRemap, (PA0<Input<Floating>>, PA1<Input<Floating>>)>>;
+
+/// Encoder on PA0 and PA1 decoded in a pin interrupt
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+pub type Player2 = InterruptEncoder<'static>;
+
+/// Potentiometer on PB1
+#[cfg(feature = "player2-pot")]
+pub type Player2 = Potentiometer<SharedAdc<'static, Adc<ADC1>>, ADC1, PB1<Analog>>;
+
+/// Up on PB14, down on PB15
+#[cfg(feature = "player2-buttons")]
+pub type Player2 = Buttons<PB14<Input<PullUp>>, PB15<Input<PullUp>>>;
+
+#[cfg(all(feature = "player2-nunchuk", not(feature = "player1-nunchuk")))]
+pub type Player2 = NunchukPort;
+
+#[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+pub type Player2 = SecondNunchukPort;
+
+/// Right buttons of a remote seen by the IR receiver
+#[cfg(feature = "player2-remote")]
+pub type Player2 = RemotePlayer<'static>;
+
+#[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+use {
+    core::cell::RefCell,
+    drivers::control::{Potentiometer, SharedAdc},
+    stm32f1xx_hal::{
+        gpio::{gpioa::PA6, gpiob::PB1, Analog},
+        pac::ADC1,
+    },
+};
+
+/// Wii Nunchuk on I2C2, SCL on PB10 and SDA on PB11
+#[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+type NunchukPort = Nunchuk<
+    stm32f1xx_hal::i2c::BlockingI2c<
+        I2C2,
+        (
+            PB10<Alternate<stm32f1xx_hal::gpio::OpenDrain>>,
+            PB11<Alternate<stm32f1xx_hal::gpio::OpenDrain>>,
+        ),
+    >,
+>;
+
+#[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+use {
+    drivers::nunchuk::Nunchuk,
+    stm32f1xx_hal::{
+        gpio::gpiob::{PB10, PB11},
+        pac::I2C2,
+    },
+};
+
+/// Nunchuk of player 2 when both players have one, they share an address so
+/// it gets I2C1 with SCL on PB6 and SDA on PB7, the bus of the i2c display
+#[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+type SecondNunchukPort = Nunchuk<
+    stm32f1xx_hal::i2c::BlockingI2c<
+        I2C1,
+        (
+            PB6<Alternate<stm32f1xx_hal::gpio::OpenDrain>>,
+            PB7<Alternate<stm32f1xx_hal::gpio::OpenDrain>>,
+        ),
+    >,
+>;
+
+#[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+use stm32f1xx_hal::{
+    gpio::gpiob::{PB6, PB7},
+    pac::I2C1,
+};
+
+#[cfg(any(
+    feature = "player1-remote",
+    feature = "player2-remote",
+    feature = "soft-encoders",
+))]
+use {
+    cortex_m::{
+        interrupt::{free, Mutex},
+        peripheral::NVIC,
+    },
+    stm32f1xx_hal::{
+        gpio::{Edge, ExtiPin},
+        pac::interrupt,
+    },
+};
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+use {
+    cortex_m::peripheral::DWT,
+    drivers::nec::{Keymap, NecReceiver, RemotePlayer},
+    stm32f1xx_hal::gpio::gpioa::PA10,
+};
+
+/// IR receiver module on PA10, edges are timed with the cycle counter
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+type IrReceiver = NecReceiver<PA10<Input<Floating>>>;
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+static IR_RECEIVER: Mutex<core::cell::RefCell<Option<IrReceiver>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+fn start_receiver(pin: PA10<Input<Floating>>) {
+    let receiver = IrReceiver::new(pin, SYSCLK_HZ / 1_000_000);
+    free(|cs| IR_RECEIVER.borrow(cs).replace(Some(receiver)));
+    unsafe { NVIC::unmask(pac::Interrupt::EXTI15_10) };
+}
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+#[interrupt]
+fn EXTI15_10() {
+    let now = DWT::cycle_count();
+    free(|cs| {
+        if let Some(receiver) = IR_RECEIVER.borrow(cs).borrow_mut().as_mut() {
+            receiver.pin().clear_interrupt_pending_bit();
+            if let Some(event) = receiver.edge(now) {
+                super::REMOTE_CODES.push(event);
+            }
+        }
+    });
+}
+
+#[cfg(feature = "soft-encoders")]
+use {
+    drivers::quadrature::{EncoderCount, InterruptEncoder, SoftEncoder},
+    embedded_hal::digital::v2::InputPin,
+};
+
+/// Pins of the player 1 encoder, decoded on every edge in EXTI9_5
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+type Encoder1 = SoftEncoder<PA8<Input<PullUp>>, PA9<Input<PullUp>>>;
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+static ENCODER1: Mutex<core::cell::RefCell<Option<Encoder1>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+/// Pins of the player 2 encoder, decoded on every edge in EXTI0 and EXTI1
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+type Encoder2 = SoftEncoder<PA0<Input<PullUp>>, PA1<Input<PullUp>>>;
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+static ENCODER2: Mutex<core::cell::RefCell<Option<Encoder2>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+/// Decodes the edge that raised the interrupt of an encoder pin
+#[cfg(feature = "soft-encoders")]
+fn encoder_edge<A, B>(
+    encoder: &Mutex<core::cell::RefCell<Option<SoftEncoder<A, B>>>>,
+    count: &EncoderCount,
+) where
+    A: InputPin + ExtiPin,
+    B: InputPin + ExtiPin,
+{
+    free(|cs| {
+        if let Some(encoder) = encoder.borrow(cs).borrow_mut().as_mut() {
+            let (a, b) = encoder.pins();
+            a.clear_interrupt_pending_bit();
+            b.clear_interrupt_pending_bit();
+            encoder.edge(count);
+        }
+    });
+}
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+#[interrupt]
+fn EXTI9_5() {
+    encoder_edge(&ENCODER1, &super::ENCODER_COUNTS[0]);
+}
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+#[interrupt]
+fn EXTI0() {
+    encoder_edge(&ENCODER2, &super::ENCODER_COUNTS[1]);
+}
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+#[interrupt]
+fn EXTI1() {
+    encoder_edge(&ENCODER2, &super::ENCODER_COUNTS[1]);
+}
+
+#[cfg(any(feature = "player1-buttons", feature = "player2-buttons"))]
+use {
+    drivers::control::Buttons,
+    stm32f1xx_hal::gpio::gpiob::{PB12, PB13, PB14, PB15},
+};
+
+/// Largest value of the 12 bit ADC
+#[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+const ADC_FULL_SCALE: u16 = 4095;
+
+#[cfg(feature = "i2c")]
+use stm32f1xx_hal::{
+    gpio::{
+        gpiob::{PB6, PB7},
+        OpenDrain,
+    },
+    i2c::{BlockingI2c, DutyCycle, Mode},
+    pac::I2C1,
+};
+
+/// Push switch of the player 1 control on PB8, pressed when low
+pub type Button1 = PB8<Input<PullUp>>;
+
+/// Push switch of the player 2 control on PB9
+pub type Button2 = PB9<Input<PullUp>>;
+
+/// Active buzzer on PA11, sounds while high
+pub type Buzzer = PA11<Output<PushPull>>;
+
 pub struct Device {
-    pub spi:
-        Spi<SPI1, Spi1NoRemap, (PA5<Alternate<PushPull>>, NoMiso, PA7<Alternate<PushPull>>), u8>,
-    pub encoder1: qei::Qei<TIM1, Tim1NoRemap, (PA8<Input<Floating>>, PA9<Input<Floating>>)>,
-    pub encoder2: qei::Qei<TIM2, Tim2NoRemap, (PA0<Input<Floating>>, PA1<Input<Floating>>)>,
+    pub spi: Spi<SPI1, Spi1NoRemap, SpiPins, u8>,
+    #[cfg(feature = "i2c")]
+    pub i2c: BlockingI2c<I2C1, (PB6<Alternate<OpenDrain>>, PB7<Alternate<OpenDrain>>)>,
+    pub player1: Player1,
+    pub player2: Player2,
+    pub button1: Button1,
+    pub button2: Button2,
+    pub buzzer: Buzzer,
     pub rand_seed: u16,
     pub key: PB5<Input<PullUp>>,
     pub led: PC13<Output<PushPull>>,
     pub dc: PA3<Output<PushPull>>,
     pub cs: PA2<Output<PushPull>>,
     pub syst: Timer<SYST>,
+    pub storage: FlashStorage,
+    #[cfg(feature = "st7735")]
+    pub rst: PB0<Output<PushPull>>,
+    #[cfg(feature = "dma")]
+    pub dma: dma1::C3,
 }
 
 impl Device {
@@ -46,7 +400,7 @@ impl Device {
         let clocks = rcc
             .cfgr
             .use_hse(8.mhz())
-            .sysclk(72.mhz())
+            .sysclk(SYSCLK_HZ.hz())
             .pclk1(36.mhz())
             .pclk2(72.mhz())
             .freeze(&mut flash.acr);
@@ -72,38 +426,365 @@ impl Device {
             &mut rcc.apb2,
         );
 
+        #[cfg(feature = "i2c")]
+        let i2c = {
+            let scl = gpiob.pb6.into_alternate_open_drain(&mut gpiob.crl);
+            let sda = gpiob.pb7.into_alternate_open_drain(&mut gpiob.crl);
+            let mode = Mode::Fast {
+                frequency: 400_000.hz(),
+                duty_cycle: DutyCycle::Ratio2to1,
+            };
+            BlockingI2c::i2c1(
+                dp.I2C1,
+                (scl, sda),
+                &mut afio.mapr,
+                mode,
+                clocks,
+                &mut rcc.apb1,
+                1000,
+                10,
+                1000,
+                1000,
+            )
+        };
+
         let mut rand_pin = gpioa.pa4.into_analog(&mut gpioa.crl);
         let mut adc = Adc::adc1(dp.ADC1, &mut rcc.apb2, clocks);
         let rand_seed: u16 = adc.read(&mut rand_pin).unwrap();
 
         let key = gpiob.pb5.into_pull_up_input(&mut gpiob.crl);
+
+        let button1 = gpiob.pb8.into_pull_up_input(&mut gpiob.crh);
+        let button2 = gpiob.pb9.into_pull_up_input(&mut gpiob.crh);
+
+        let buzzer = gpioa.pa11.into_push_pull_output(&mut gpioa.crh);
+
+        #[cfg(feature = "st7735")]
+        let rst = gpiob.pb0.into_push_pull_output(&mut gpiob.crl);
         let led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
 
-        let encoder1 = Timer::tim1(dp.TIM1, &clocks, &mut rcc.apb2).qei::<Tim1NoRemap, _>(
-            (gpioa.pa8, gpioa.pa9),
-            &mut afio.mapr,
-            Default::default(),
+        #[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+        let adc: &'static RefCell<_> =
+            cortex_m::singleton!(: RefCell<Adc<ADC1>> = RefCell::new(adc)).unwrap();
+
+        #[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+        let nunchuk = {
+            use stm32f1xx_hal::i2c::{BlockingI2c, Mode};
+
+            let scl = gpiob.pb10.into_alternate_open_drain(&mut gpiob.crh);
+            let sda = gpiob.pb11.into_alternate_open_drain(&mut gpiob.crh);
+            let mode = Mode::Standard {
+                frequency: 100_000.hz(),
+            };
+            NunchukPort::new(BlockingI2c::i2c2(
+                dp.I2C2,
+                (scl, sda),
+                mode,
+                clocks,
+                &mut rcc.apb1,
+                1000,
+                10,
+                1000,
+                1000,
+            ))
+        };
+
+        #[cfg(not(any(
+            feature = "player1-pot",
+            feature = "player1-buttons",
+            feature = "player1-nunchuk",
+            feature = "player1-remote",
+            feature = "soft-encoders",
+        )))]
+        let player1 = Player1::new(
+            Timer::tim1(dp.TIM1, &clocks, &mut rcc.apb2).qei::<Tim1NoRemap, _>(
+                (gpioa.pa8, gpioa.pa9),
+                &mut afio.mapr,
+                Default::default(),
+            ),
         );
 
-        let encoder2 = Timer::tim2(dp.TIM2, &clocks, &mut rcc.apb1).qei::<Tim2NoRemap, _>(
-            (gpioa.pa0, gpioa.pa1),
-            &mut afio.mapr,
-            Default::default(),
+        #[cfg(all(
+            feature = "soft-encoders",
+            not(any(
+                feature = "player1-pot",
+                feature = "player1-buttons",
+                feature = "player1-nunchuk",
+                feature = "player1-remote",
+            )),
+        ))]
+        let player1 = {
+            let mut a = gpioa.pa8.into_pull_up_input(&mut gpioa.crh);
+            let mut b = gpioa.pa9.into_pull_up_input(&mut gpioa.crh);
+            a.make_interrupt_source(&mut afio);
+            a.trigger_on_edge(&dp.EXTI, Edge::RISING_FALLING);
+            a.enable_interrupt(&dp.EXTI);
+            b.make_interrupt_source(&mut afio);
+            b.trigger_on_edge(&dp.EXTI, Edge::RISING_FALLING);
+            b.enable_interrupt(&dp.EXTI);
+
+            free(|cs| ENCODER1.borrow(cs).replace(Some(Encoder1::new(a, b))));
+            unsafe { NVIC::unmask(pac::Interrupt::EXTI9_5) };
+            Player1::new(&super::ENCODER_COUNTS[0])
+        };
+
+        #[cfg(feature = "player1-pot")]
+        let player1 = Player1::new(
+            SharedAdc(adc),
+            gpioa.pa6.into_analog(&mut gpioa.crl),
+            ADC_FULL_SCALE,
+            pong::PADDLE_STEPS,
+        );
+
+        #[cfg(feature = "player1-buttons")]
+        let player1 = Player1::new(
+            gpiob.pb12.into_pull_up_input(&mut gpiob.crh),
+            gpiob.pb13.into_pull_up_input(&mut gpiob.crh),
         );
+
+        #[cfg(feature = "player1-nunchuk")]
+        let player1 = nunchuk;
+
+        #[cfg(feature = "player1-remote")]
+        let player1 = Player1::new(&super::REMOTE_CODES, Keymap::LEFT);
+
+        #[cfg(not(any(
+            feature = "player2-pot",
+            feature = "player2-buttons",
+            feature = "player2-nunchuk",
+            feature = "player2-remote",
+            feature = "soft-encoders",
+        )))]
+        let player2 = Player2::new(
+            Timer::tim2(dp.TIM2, &clocks, &mut rcc.apb1).qei::<Tim2NoRemap, _>(
+                (gpioa.pa0, gpioa.pa1),
+                &mut afio.mapr,
+                Default::default(),
+            ),
+        );
+
+        #[cfg(all(
+            feature = "soft-encoders",
+            not(any(
+                feature = "player2-pot",
+                feature = "player2-buttons",
+                feature = "player2-nunchuk",
+                feature = "player2-remote",
+            )),
+        ))]
+        let player2 = {
+            let mut a = gpioa.pa0.into_pull_up_input(&mut gpioa.crl);
+            let mut b = gpioa.pa1.into_pull_up_input(&mut gpioa.crl);
+            a.make_interrupt_source(&mut afio);
+            a.trigger_on_edge(&dp.EXTI, Edge::RISING_FALLING);
+            a.enable_interrupt(&dp.EXTI);
+            b.make_interrupt_source(&mut afio);
+            b.trigger_on_edge(&dp.EXTI, Edge::RISING_FALLING);
+            b.enable_interrupt(&dp.EXTI);
+
+            free(|cs| ENCODER2.borrow(cs).replace(Some(Encoder2::new(a, b))));
+            unsafe {
+                NVIC::unmask(pac::Interrupt::EXTI0);
+                NVIC::unmask(pac::Interrupt::EXTI1);
+            }
+            Player2::new(&super::ENCODER_COUNTS[1])
+        };
+
+        #[cfg(feature = "player2-pot")]
+        let player2 = Player2::new(
+            SharedAdc(adc),
+            gpiob.pb1.into_analog(&mut gpiob.crl),
+            ADC_FULL_SCALE,
+            pong::PADDLE_STEPS,
+        );
+
+        #[cfg(feature = "player2-buttons")]
+        let player2 = Player2::new(
+            gpiob.pb14.into_pull_up_input(&mut gpiob.crh),
+            gpiob.pb15.into_pull_up_input(&mut gpiob.crh),
+        );
+
+        #[cfg(all(feature = "player2-nunchuk", not(feature = "player1-nunchuk")))]
+        let player2 = nunchuk;
+
+        #[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+        let player2 = {
+            use stm32f1xx_hal::i2c::{BlockingI2c, Mode};
+
+            let scl = gpiob.pb6.into_alternate_open_drain(&mut gpiob.crl);
+            let sda = gpiob.pb7.into_alternate_open_drain(&mut gpiob.crl);
+            let mode = Mode::Standard {
+                frequency: 100_000.hz(),
+            };
+            Player2::new(BlockingI2c::i2c1(
+                dp.I2C1,
+                (scl, sda),
+                &mut afio.mapr,
+                mode,
+                clocks,
+                &mut rcc.apb1,
+                1000,
+                10,
+                1000,
+                1000,
+            ))
+        };
+
+        #[cfg(feature = "player2-remote")]
+        let player2 = Player2::new(&super::REMOTE_CODES, Keymap::RIGHT);
+
+        #[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+        {
+            let (mut dcb, mut dwt) = (cp.DCB, cp.DWT);
+            dcb.enable_trace();
+            dwt.enable_cycle_counter();
+
+            let mut pin = gpioa.pa10.into_floating_input(&mut gpioa.crh);
+            pin.make_interrupt_source(&mut afio);
+            pin.trigger_on_edge(&dp.EXTI, Edge::RISING_FALLING);
+            pin.enable_interrupt(&dp.EXTI);
+            start_receiver(pin);
+        }
+
         let syst = Timer::syst(cp.SYST, &clocks);
 
+        #[cfg(feature = "dma")]
+        let dma = dp.DMA1.split(&mut rcc.ahb).3;
+
         Self {
             spi,
-            encoder1,
-            encoder2,
+            #[cfg(feature = "i2c")]
+            i2c,
+            player1,
+            player2,
+            button1,
+            button2,
+            buzzer,
             rand_seed,
             key,
             led,
             dc,
             cs,
             syst,
+            storage: FlashStorage { flash },
+            #[cfg(feature = "st7735")]
+            rst,
+            #[cfg(feature = "dma")]
+            dma,
+        }
+    }
+}
+
+/// Offset of the last 1 KiB page of a 64 KiB part
+const STORAGE_OFFSET: u32 = 63 * 1024;
+
+/// Settings in the last page of the flash
+pub struct FlashStorage {
+    flash: flash::Parts,
+}
+
+impl Storage for FlashStorage {
+    type Error = flash::Error;
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), flash::Error> {
+        let writer = self.flash.writer(SectorSize::Sz1K, FlashSize::Sz64K);
+        record.copy_from_slice(writer.read(STORAGE_OFFSET, RECORD_SIZE)?);
+        Ok(())
+    }
+
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), flash::Error> {
+        let mut writer = self.flash.writer(SectorSize::Sz1K, FlashSize::Sz64K);
+        writer.erase(STORAGE_OFFSET, 1024)?;
+        writer.write(STORAGE_OFFSET, record)
+    }
+}
+
+#[cfg(feature = "dma")]
+type SpiTx = SpiTxDma<SPI1, Spi1NoRemap, SpiPins, dma1::C3>;
+
+#[cfg(feature = "dma")]
+enum DmaState {
+    Idle(SpiTx, &'static mut [u8]),
+    Busy(Transfer<R, &'static mut [u8], SpiTx>),
+}
+
+/// Sends frames to the display over SPI1 with DMA1 channel 3, DC is held
+/// high for data except while a command is sent
+#[cfg(feature = "dma")]
+pub struct DisplayDma {
+    state: Option<DmaState>,
+    dc: PA3<Output<PushPull>>,
+}
+
+#[cfg(feature = "dma")]
+impl DisplayDma {
+    pub fn new(
+        spi: Spi<SPI1, Spi1NoRemap, SpiPins, u8>,
+        mut dc: PA3<Output<PushPull>>,
+        channel: dma1::C3,
+        spare: &'static mut [u8],
+    ) -> Self {
+        dc.set_high().ok();
+
+        Self {
+            state: Some(DmaState::Idle(spi.with_tx_dma(channel), spare)),
+            dc,
+        }
+    }
+}
+
+#[cfg(feature = "dma")]
+impl WriteDma for DisplayDma {
+    fn swap(&mut self, buffer: &'static mut [u8]) -> &'static mut [u8] {
+        let (tx, previous) = match self.state.take().unwrap() {
+            DmaState::Idle(tx, spare) => (tx, spare),
+            DmaState::Busy(transfer) => {
+                let (previous, tx) = transfer.wait();
+                (tx, previous)
+            }
+        };
+
+        self.state = Some(DmaState::Busy(tx.write(buffer)));
+        previous
+    }
+
+    fn is_busy(&mut self) -> bool {
+        match &self.state {
+            Some(DmaState::Busy(transfer)) => !transfer.is_done(),
+            _ => false,
         }
     }
+
+    fn command(&mut self, bytes: &[u8]) {
+        let state = match self.state.take().unwrap() {
+            DmaState::Busy(transfer) => {
+                let (previous, tx) = transfer.wait();
+                DmaState::Idle(tx, previous)
+            }
+            idle => idle,
+        };
+        self.state = Some(state);
+
+        self.dc.set_low().ok();
+        write_spi1(bytes);
+        self.dc.set_high().ok();
+    }
+}
+
+/// Writes bytes straight to the data register of SPI1 while its DMA channel
+/// is stopped, returns once the last one is out so DC can change
+#[cfg(feature = "dma")]
+fn write_spi1(bytes: &[u8]) {
+    let spi = unsafe { &*SPI1::ptr() };
+    let idle = || spi.sr.read().txe().bit_is_set() && spi.sr.read().bsy().bit_is_clear();
+
+    // the last byte of a frame may still be shifting out
+    while !idle() {}
+    for &byte in bytes {
+        while spi.sr.read().txe().bit_is_clear() {}
+        spi.dr.write(|w| w.dr().bits(byte as u16));
+    }
+    while !idle() {}
 }
 
 pub unsafe fn blink_loop(start: u32) -> ! {
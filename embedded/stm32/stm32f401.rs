@@ -1,11 +1,16 @@
 use {
     crate::blink::Blink,
     cortex_m::peripheral::SYST,
+    drivers::{
+        control::PlayerEncoder,
+        storage::{Storage, RECORD_SIZE},
+    },
     embedded_hal::spi::MODE_0,
     stm32f4xx_hal::{
         adc::Adc,
         delay::Delay,
-        gpio::{Alternate, Floating, Input, NoPin, Output, Pin, PullUp, PushPull},
+        flash::{self, FlashExt},
+        gpio::{Alternate, Input, NoPin, Output, Pin, PullUp, PushPull},
         pac::{self, SPI1, TIM1, TIM4},
         prelude::*,
         qei,
@@ -14,26 +19,327 @@ use {
     },
 };
 
+#[cfg(feature = "dma")]
+use {
+    drivers::dma::WriteDma,
+    stm32f4xx_hal::{
+        dma::{
+            config::DmaConfig, traits::StreamISR, MemoryToPeripheral, Stream3, StreamsTuple,
+            Transfer,
+        },
+        pac::DMA2,
+    },
+};
+
 pub use stm32f4xx_hal::prelude;
 
-pub struct Device {
-    pub spi: Spi<
-        SPI1,
+pub const SYSCLK_HZ: u32 = 84_000_000;
+
+type SpiPins = (Pin<Alternate<5>, 'A', 5>, NoPin, Pin<Alternate<5>, 'A', 7>);
+
+#[cfg(not(any(
+    feature = "player1-pot",
+    feature = "player1-buttons",
+    feature = "player1-nunchuk",
+    feature = "player1-remote",
+    feature = "soft-encoders",
+)))]
+pub type Player1 =
+    PlayerEncoder<qei::Qei<TIM1, (Pin<Alternate<1>, 'A', 8>, Pin<Alternate<1>, 'A', 9>)>>;
+
+/// Encoder on PA8 and PA9 decoded in a pin interrupt
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+pub type Player1 = InterruptEncoder<'static>;
+
+/// Potentiometer on PA1
+#[cfg(feature = "player1-pot")]
+pub type Player1 = Potentiometer<SharedAdc<'static, Adc<ADC1>>, ADC1, Pin<Analog, 'A', 1>>;
+
+/// Up on PB12, down on PB13
+#[cfg(feature = "player1-buttons")]
+pub type Player1 = Buttons<Pin<Input<PullUp>, 'B', 12>, Pin<Input<PullUp>, 'B', 13>>;
+
+#[cfg(feature = "player1-nunchuk")]
+pub type Player1 = NunchukPort;
+
+/// Left buttons of a remote seen by the IR receiver
+#[cfg(feature = "player1-remote")]
+pub type Player1 = RemotePlayer<'static>;
+
+#[cfg(not(any(
+    feature = "player2-pot",
+    feature = "player2-buttons",
+    feature = "player2-nunchuk",
+    feature = "player2-remote",
+    feature = "soft-encoders",
+)))]
+pub type Player2 =
+    PlayerEncoder<qei::Qei<TIM4, (Pin<Alternate<2>, 'B', 6>, Pin<Alternate<2>, 'B', 7>)>>;
+
+/// Encoder on PB6 and PB7 decoded in a pin interrupt
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+pub type Player2 = InterruptEncoder<'static>;
+
+/// Potentiometer on PB1
+#[cfg(feature = "player2-pot")]
+pub type Player2 = Potentiometer<SharedAdc<'static, Adc<ADC1>>, ADC1, Pin<Analog, 'B', 1>>;
+
+/// Up on PB14, down on PB15
+#[cfg(feature = "player2-buttons")]
+pub type Player2 = Buttons<Pin<Input<PullUp>, 'B', 14>, Pin<Input<PullUp>, 'B', 15>>;
+
+#[cfg(all(feature = "player2-nunchuk", not(feature = "player1-nunchuk")))]
+pub type Player2 = NunchukPort;
+
+#[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+pub type Player2 = SecondNunchukPort;
+
+/// Right buttons of a remote seen by the IR receiver
+#[cfg(feature = "player2-remote")]
+pub type Player2 = RemotePlayer<'static>;
+
+#[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+use {
+    core::cell::RefCell,
+    drivers::control::{Potentiometer, SharedAdc},
+    stm32f4xx_hal::{gpio::Analog, pac::ADC1},
+};
+
+/// Wii Nunchuk on I2C2, SCL on PB10 and SDA on PB3
+#[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+type NunchukPort = Nunchuk<
+    stm32f4xx_hal::i2c::I2c<
+        I2C2,
         (
-            Pin<Input<Floating>, 'A', 5>,
-            NoPin,
-            Pin<Input<Floating>, 'A', 7>,
+            Pin<stm32f4xx_hal::gpio::AlternateOD<4>, 'B', 10>,
+            Pin<stm32f4xx_hal::gpio::AlternateOD<9>, 'B', 3>,
         ),
-        TransferModeNormal,
     >,
-    pub encoder1: qei::Qei<TIM1, (Pin<Alternate<1>, 'A', 8>, Pin<Alternate<1>, 'A', 9>)>,
-    pub encoder2: qei::Qei<TIM4, (Pin<Alternate<2>, 'B', 6>, Pin<Alternate<2>, 'B', 7>)>,
+>;
+
+#[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+use {drivers::nunchuk::Nunchuk, stm32f4xx_hal::pac::I2C2};
+
+/// Nunchuk of player 2 when both players have one, they share an address so
+/// it gets I2C1 with SCL on PB8 and SDA on PB9, the bus of the i2c display
+#[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+type SecondNunchukPort = Nunchuk<
+    stm32f4xx_hal::i2c::I2c<
+        stm32f4xx_hal::pac::I2C1,
+        (
+            Pin<stm32f4xx_hal::gpio::AlternateOD<4>, 'B', 8>,
+            Pin<stm32f4xx_hal::gpio::AlternateOD<4>, 'B', 9>,
+        ),
+    >,
+>;
+
+#[cfg(any(
+    feature = "player1-remote",
+    feature = "player2-remote",
+    feature = "soft-encoders",
+))]
+use {
+    cortex_m::{
+        interrupt::{free, Mutex},
+        peripheral::NVIC,
+    },
+    stm32f4xx_hal::{
+        gpio::{Edge, ExtiPin},
+        pac::interrupt,
+    },
+};
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+use {
+    cortex_m::peripheral::DWT,
+    drivers::nec::{Keymap, NecReceiver, RemotePlayer},
+    stm32f4xx_hal::gpio::Floating,
+};
+
+/// IR receiver module on PA10, edges are timed with the cycle counter
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+type IrReceiver = NecReceiver<Pin<Input<Floating>, 'A', 10>>;
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+static IR_RECEIVER: Mutex<core::cell::RefCell<Option<IrReceiver>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+fn start_receiver(pin: Pin<Input<Floating>, 'A', 10>) {
+    let receiver = IrReceiver::new(pin, SYSCLK_HZ / 1_000_000);
+    free(|cs| IR_RECEIVER.borrow(cs).replace(Some(receiver)));
+    unsafe { NVIC::unmask(pac::Interrupt::EXTI15_10) };
+}
+
+#[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+#[interrupt]
+fn EXTI15_10() {
+    let now = DWT::cycle_count();
+    free(|cs| {
+        if let Some(receiver) = IR_RECEIVER.borrow(cs).borrow_mut().as_mut() {
+            receiver.pin().clear_interrupt_pending_bit();
+            if let Some(event) = receiver.edge(now) {
+                super::REMOTE_CODES.push(event);
+            }
+        }
+    });
+}
+
+#[cfg(feature = "soft-encoders")]
+use {
+    drivers::quadrature::{EncoderCount, InterruptEncoder, SoftEncoder},
+    embedded_hal::digital::v2::InputPin,
+};
+
+/// Pins of the player 1 encoder, decoded on every edge in EXTI9_5
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+type Encoder1 = SoftEncoder<Pin<Input<PullUp>, 'A', 8>, Pin<Input<PullUp>, 'A', 9>>;
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player1-pot",
+        feature = "player1-buttons",
+        feature = "player1-nunchuk",
+        feature = "player1-remote",
+    )),
+))]
+static ENCODER1: Mutex<core::cell::RefCell<Option<Encoder1>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+/// Pins of the player 2 encoder, decoded on every edge in EXTI9_5
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+type Encoder2 = SoftEncoder<Pin<Input<PullUp>, 'B', 6>, Pin<Input<PullUp>, 'B', 7>>;
+
+#[cfg(all(
+    feature = "soft-encoders",
+    not(any(
+        feature = "player2-pot",
+        feature = "player2-buttons",
+        feature = "player2-nunchuk",
+        feature = "player2-remote",
+    )),
+))]
+static ENCODER2: Mutex<core::cell::RefCell<Option<Encoder2>>> =
+    Mutex::new(core::cell::RefCell::new(None));
+
+/// Decodes the edge that raised the interrupt of an encoder pin
+#[cfg(feature = "soft-encoders")]
+fn encoder_edge<A, B>(
+    encoder: &Mutex<core::cell::RefCell<Option<SoftEncoder<A, B>>>>,
+    count: &EncoderCount,
+) where
+    A: InputPin + ExtiPin,
+    B: InputPin + ExtiPin,
+{
+    free(|cs| {
+        if let Some(encoder) = encoder.borrow(cs).borrow_mut().as_mut() {
+            let (a, b) = encoder.pins();
+            a.clear_interrupt_pending_bit();
+            b.clear_interrupt_pending_bit();
+            encoder.edge(count);
+        }
+    });
+}
+
+/// Lines 5 to 9 have one interrupt, the pins of both encoders are on them
+#[cfg(feature = "soft-encoders")]
+#[interrupt]
+fn EXTI9_5() {
+    #[cfg(all(
+        feature = "soft-encoders",
+        not(any(
+            feature = "player1-pot",
+            feature = "player1-buttons",
+            feature = "player1-nunchuk",
+            feature = "player1-remote",
+        )),
+    ))]
+    encoder_edge(&ENCODER1, &super::ENCODER_COUNTS[0]);
+
+    #[cfg(all(
+        feature = "soft-encoders",
+        not(any(
+            feature = "player2-pot",
+            feature = "player2-buttons",
+            feature = "player2-nunchuk",
+            feature = "player2-remote",
+        )),
+    ))]
+    encoder_edge(&ENCODER2, &super::ENCODER_COUNTS[1]);
+}
+
+#[cfg(any(feature = "player1-buttons", feature = "player2-buttons"))]
+use drivers::control::Buttons;
+
+/// Largest value of the 12 bit ADC
+#[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+const ADC_FULL_SCALE: u16 = 4095;
+
+#[cfg(feature = "i2c")]
+use stm32f4xx_hal::{gpio::AlternateOD, i2c::I2c, pac::I2C1};
+
+/// Push switch of the player 1 control on PB4, pressed when low
+pub type Button1 = Pin<Input<PullUp>, 'B', 4>;
+
+/// Push switch of the player 2 control on PB5
+pub type Button2 = Pin<Input<PullUp>, 'B', 5>;
+
+/// Active buzzer on PA11, sounds while high
+pub type Buzzer = Pin<Output<PushPull>, 'A', 11>;
+
+pub struct Device {
+    pub spi: Spi<SPI1, SpiPins, TransferModeNormal>,
+    #[cfg(feature = "i2c")]
+    pub i2c: I2c<I2C1, (Pin<AlternateOD<4>, 'B', 8>, Pin<AlternateOD<4>, 'B', 9>)>,
+    pub player1: Player1,
+    pub player2: Player2,
+    pub button1: Button1,
+    pub button2: Button2,
+    pub buzzer: Buzzer,
     pub rand_seed: u16,
     pub key: Pin<Input<PullUp>, 'A', 0>,
     pub led: Pin<Output<PushPull>, 'C', 13>,
     pub dc: Pin<Output<PushPull>, 'A', 3>,
     pub cs: Pin<Output<PushPull>, 'A', 2>,
     pub syst: Timer<SYST>,
+    pub storage: FlashStorage,
+    #[cfg(feature = "st7735")]
+    pub rst: Pin<Output<PushPull>, 'B', 0>,
+    #[cfg(feature = "dma")]
+    pub dma: Stream3<DMA2>,
 }
 
 impl Device {
@@ -46,7 +352,7 @@ impl Device {
         let clocks = rcc
             .cfgr
             .use_hse(25.mhz())
-            .sysclk(84.mhz())
+            .sysclk(SYSCLK_HZ.hz())
             .hclk(84.mhz())
             .pclk2(84.mhz())
             .pclk1(42.mhz())
@@ -59,44 +365,327 @@ impl Device {
         let dc = gpioa.pa3.into_push_pull_output();
         let cs = gpioa.pa2.into_push_pull_output();
 
-        let sck = gpioa.pa5;
-        let mosi = gpioa.pa7;
+        // set up front so releasing the SPI for DMA keeps them on SPI1
+        let sck = gpioa.pa5.into_alternate();
+        let mosi = gpioa.pa7.into_alternate();
 
         let spi = Spi::new(dp.SPI1, (sck, NoPin, mosi), MODE_0, 28.mhz(), clocks);
 
-        let encoder1 = qei::Qei::new(
+        #[cfg(feature = "i2c")]
+        let i2c = {
+            let scl = gpiob.pb8.into_alternate_open_drain();
+            let sda = gpiob.pb9.into_alternate_open_drain();
+            I2c::new(dp.I2C1, (scl, sda), 400.khz(), clocks)
+        };
+
+        let key = gpioa.pa0.into_pull_up_input();
+
+        let button1 = gpiob.pb4.into_pull_up_input();
+        let button2 = gpiob.pb5.into_pull_up_input();
+
+        let buzzer = gpioa.pa11.into_push_pull_output();
+
+        #[cfg(feature = "st7735")]
+        let rst = gpiob.pb0.into_push_pull_output();
+        let led = gpioc.pc13.into_push_pull_output();
+
+        #[cfg(any(
+            feature = "player1-remote",
+            feature = "player2-remote",
+            feature = "soft-encoders",
+        ))]
+        let (mut syscfg, mut exti) = (dp.SYSCFG.constrain(), dp.EXTI);
+
+        #[cfg(any(feature = "player1-remote", feature = "player2-remote"))]
+        {
+            let (mut dcb, mut dwt) = (cp.DCB, cp.DWT);
+            dcb.enable_trace();
+            dwt.enable_cycle_counter();
+
+            let mut pin = gpioa.pa10.into_floating_input();
+            pin.make_interrupt_source(&mut syscfg);
+            pin.trigger_on_edge(&mut exti, Edge::RisingFalling);
+            pin.enable_interrupt(&mut exti);
+            start_receiver(pin);
+        }
+
+        let syst = Timer::syst(cp.SYST, &clocks);
+
+        #[cfg(feature = "dma")]
+        let dma = StreamsTuple::new(dp.DMA2).3;
+
+        let mut rand_pin = gpioa.pa4.into_analog();
+        let mut adc = Adc::adc1(dp.ADC1, false, Default::default());
+        let rand_seed: u16 = adc.read(&mut rand_pin).unwrap();
+
+        #[cfg(any(feature = "player1-pot", feature = "player2-pot"))]
+        let adc: &'static RefCell<_> =
+            cortex_m::singleton!(: RefCell<Adc<ADC1>> = RefCell::new(adc)).unwrap();
+
+        #[cfg(any(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+        let nunchuk = {
+            let scl = gpiob.pb10.into_alternate_open_drain();
+            let sda = gpiob.pb3.into_alternate_open_drain();
+            NunchukPort::new(stm32f4xx_hal::i2c::I2c::new(
+                dp.I2C2,
+                (scl, sda),
+                100.khz(),
+                clocks,
+            ))
+        };
+
+        #[cfg(not(any(
+            feature = "player1-pot",
+            feature = "player1-buttons",
+            feature = "player1-nunchuk",
+            feature = "player1-remote",
+            feature = "soft-encoders",
+        )))]
+        let player1 = Player1::new(qei::Qei::new(
             dp.TIM1,
             (gpioa.pa8.into_alternate(), gpioa.pa9.into_alternate()),
+        ));
+
+        #[cfg(all(
+            feature = "soft-encoders",
+            not(any(
+                feature = "player1-pot",
+                feature = "player1-buttons",
+                feature = "player1-nunchuk",
+                feature = "player1-remote",
+            )),
+        ))]
+        let player1 = {
+            let mut a = gpioa.pa8.into_pull_up_input();
+            let mut b = gpioa.pa9.into_pull_up_input();
+            a.make_interrupt_source(&mut syscfg);
+            a.trigger_on_edge(&mut exti, Edge::RisingFalling);
+            a.enable_interrupt(&mut exti);
+            b.make_interrupt_source(&mut syscfg);
+            b.trigger_on_edge(&mut exti, Edge::RisingFalling);
+            b.enable_interrupt(&mut exti);
+
+            free(|cs| ENCODER1.borrow(cs).replace(Some(Encoder1::new(a, b))));
+            unsafe { NVIC::unmask(pac::Interrupt::EXTI9_5) };
+            Player1::new(&super::ENCODER_COUNTS[0])
+        };
+
+        #[cfg(feature = "player1-pot")]
+        let player1 = Player1::new(
+            SharedAdc(adc),
+            gpioa.pa1.into_analog(),
+            ADC_FULL_SCALE,
+            pong::PADDLE_STEPS,
         );
 
-        let encoder2 = qei::Qei::new(
+        #[cfg(feature = "player1-buttons")]
+        let player1 = Player1::new(
+            gpiob.pb12.into_pull_up_input(),
+            gpiob.pb13.into_pull_up_input(),
+        );
+
+        #[cfg(feature = "player1-nunchuk")]
+        let player1 = nunchuk;
+
+        #[cfg(feature = "player1-remote")]
+        let player1 = Player1::new(&super::REMOTE_CODES, Keymap::LEFT);
+
+        #[cfg(not(any(
+            feature = "player2-pot",
+            feature = "player2-buttons",
+            feature = "player2-nunchuk",
+            feature = "player2-remote",
+            feature = "soft-encoders",
+        )))]
+        let player2 = Player2::new(qei::Qei::new(
             dp.TIM4,
             (gpiob.pb6.into_alternate(), gpiob.pb7.into_alternate()),
+        ));
+
+        #[cfg(all(
+            feature = "soft-encoders",
+            not(any(
+                feature = "player2-pot",
+                feature = "player2-buttons",
+                feature = "player2-nunchuk",
+                feature = "player2-remote",
+            )),
+        ))]
+        let player2 = {
+            let mut a = gpiob.pb6.into_pull_up_input();
+            let mut b = gpiob.pb7.into_pull_up_input();
+            a.make_interrupt_source(&mut syscfg);
+            a.trigger_on_edge(&mut exti, Edge::RisingFalling);
+            a.enable_interrupt(&mut exti);
+            b.make_interrupt_source(&mut syscfg);
+            b.trigger_on_edge(&mut exti, Edge::RisingFalling);
+            b.enable_interrupt(&mut exti);
+
+            free(|cs| ENCODER2.borrow(cs).replace(Some(Encoder2::new(a, b))));
+            unsafe { NVIC::unmask(pac::Interrupt::EXTI9_5) };
+            Player2::new(&super::ENCODER_COUNTS[1])
+        };
+
+        #[cfg(feature = "player2-pot")]
+        let player2 = Player2::new(
+            SharedAdc(adc),
+            gpiob.pb1.into_analog(),
+            ADC_FULL_SCALE,
+            pong::PADDLE_STEPS,
         );
 
-        let key = gpioa.pa0.into_pull_up_input();
-        let led = gpioc.pc13.into_push_pull_output();
+        #[cfg(feature = "player2-buttons")]
+        let player2 = Player2::new(
+            gpiob.pb14.into_pull_up_input(),
+            gpiob.pb15.into_pull_up_input(),
+        );
 
-        let syst = Timer::syst(cp.SYST, &clocks);
+        #[cfg(all(feature = "player2-nunchuk", not(feature = "player1-nunchuk")))]
+        let player2 = nunchuk;
 
-        let mut rand_pin = gpioa.pa4.into_analog();
-        let mut adc = Adc::adc1(dp.ADC1, false, Default::default());
-        let rand_seed: u16 = adc.read(&mut rand_pin).unwrap();
+        #[cfg(all(feature = "player1-nunchuk", feature = "player2-nunchuk"))]
+        let player2 = {
+            let scl = gpiob.pb8.into_alternate_open_drain();
+            let sda = gpiob.pb9.into_alternate_open_drain();
+            Player2::new(stm32f4xx_hal::i2c::I2c::new(
+                dp.I2C1,
+                (scl, sda),
+                100.khz(),
+                clocks,
+            ))
+        };
+
+        #[cfg(feature = "player2-remote")]
+        let player2 = Player2::new(&super::REMOTE_CODES, Keymap::RIGHT);
 
         Self {
             spi,
-            encoder1,
-            encoder2,
+            #[cfg(feature = "i2c")]
+            i2c,
+            player1,
+            player2,
+            button1,
+            button2,
+            buzzer,
             rand_seed,
             key,
             led,
             dc,
             cs,
             syst,
+            storage: FlashStorage { flash: dp.FLASH },
+            #[cfg(feature = "st7735")]
+            rst,
+            #[cfg(feature = "dma")]
+            dma,
         }
     }
 }
 
+/// Last 128 KiB sector of a 256 KiB part
+const STORAGE_SECTOR: u8 = 5;
+const STORAGE_OFFSET: usize = 0x20000;
+
+/// Settings in the last sector of the flash
+pub struct FlashStorage {
+    flash: pac::FLASH,
+}
+
+impl Storage for FlashStorage {
+    type Error = flash::Error;
+
+    fn read(&mut self, record: &mut [u8; RECORD_SIZE]) -> Result<(), flash::Error> {
+        record.copy_from_slice(&self.flash.read()[STORAGE_OFFSET..STORAGE_OFFSET + RECORD_SIZE]);
+        Ok(())
+    }
+
+    fn write(&mut self, record: &[u8; RECORD_SIZE]) -> Result<(), flash::Error> {
+        let mut flash = self.flash.unlocked();
+        flash.erase(STORAGE_SECTOR)?;
+        flash.program(STORAGE_OFFSET, record.iter())
+    }
+}
+
+/// Sends frames to the display over SPI1 with DMA2 stream 3 channel 3, DC
+/// is held high for data except while a command is sent
+#[cfg(feature = "dma")]
+pub struct DisplayDma {
+    transfer: Transfer<Stream3<DMA2>, SPI1, MemoryToPeripheral, &'static mut [u8], 3>,
+    started: bool,
+    dc: Pin<Output<PushPull>, 'A', 3>,
+    _pins: SpiPins,
+}
+
+#[cfg(feature = "dma")]
+impl DisplayDma {
+    pub fn new(
+        spi: Spi<SPI1, SpiPins, TransferModeNormal>,
+        mut dc: Pin<Output<PushPull>, 'A', 3>,
+        stream: Stream3<DMA2>,
+        spare: &'static mut [u8],
+    ) -> Self {
+        dc.set_high();
+
+        let (spi1, pins) = spi.release();
+        spi1.cr2.modify(|_, w| w.txdmaen().enabled());
+
+        let transfer = Transfer::init_memory_to_peripheral(
+            stream,
+            spi1,
+            spare,
+            None,
+            DmaConfig::default().memory_increment(true),
+        );
+
+        Self {
+            transfer,
+            started: false,
+            dc,
+            _pins: pins,
+        }
+    }
+}
+
+#[cfg(feature = "dma")]
+impl WriteDma for DisplayDma {
+    fn swap(&mut self, buffer: &'static mut [u8]) -> &'static mut [u8] {
+        while self.is_busy() {}
+        self.transfer.clear_transfer_complete_interrupt();
+        self.started = true;
+
+        let (previous, _) = self.transfer.next_transfer(buffer).ok().unwrap();
+        previous
+    }
+
+    fn is_busy(&mut self) -> bool {
+        self.started && !Stream3::<DMA2>::get_transfer_complete_flag()
+    }
+
+    fn command(&mut self, bytes: &[u8]) {
+        while self.is_busy() {}
+
+        self.dc.set_low();
+        write_spi1(bytes);
+        self.dc.set_high();
+    }
+}
+
+/// Writes bytes straight to the data register of SPI1 while its DMA stream
+/// is stopped, returns once the last one is out so DC can change
+#[cfg(feature = "dma")]
+fn write_spi1(bytes: &[u8]) {
+    let spi = unsafe { &*SPI1::ptr() };
+    let idle = || spi.sr.read().txe().bit_is_set() && spi.sr.read().bsy().bit_is_clear();
+
+    // the last byte of a frame may still be shifting out
+    while !idle() {}
+    for &byte in bytes {
+        while spi.sr.read().txe().bit_is_clear() {}
+        spi.dr.write(|w| w.dr().bits(byte as u16));
+    }
+    while !idle() {}
+}
+
 pub unsafe fn blink_loop(start: u32) -> ! {
     let dp = pac::Peripherals::steal();
     let cp = cortex_m::Peripherals::steal();
@@ -1,7 +1,8 @@
 MEMORY
 {
   /* NOTE K = KiBi = 1024 bytes */
-  FLASH : ORIGIN = 0x08000000, LENGTH = 64K 
+  /* the last 1K page of the 64K part holds the settings */
+  FLASH : ORIGIN = 0x08000000, LENGTH = 63K
   RAM : ORIGIN = 0x20000000, LENGTH = 20K
 }
 
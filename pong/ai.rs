@@ -0,0 +1,160 @@
+use super::{Pong, Side, Status};
+
+/// Frames a computer player waits before it serves
+const SERVE_DELAY: u32 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    /// Controller steps the paddle moves per frame at most
+    fn speed(self) -> i32 {
+        match self {
+            Self::Easy => 1,
+            Self::Normal => 2,
+            Self::Hard => 3,
+        }
+    }
+
+    /// Frames between two looks at the ball
+    fn reaction(self) -> u32 {
+        match self {
+            Self::Easy => 15,
+            Self::Normal => 8,
+            Self::Hard => 3,
+        }
+    }
+
+    /// Largest aiming error in sixteenths of the paddle height
+    fn error(self) -> i32 {
+        match self {
+            Self::Easy => 12,
+            Self::Normal => 6,
+            Self::Hard => 2,
+        }
+    }
+
+    /// Whether the bounces off the borders are worked out in advance
+    fn predicts(self) -> bool {
+        self == Self::Hard
+    }
+}
+
+/// Computer player that moves one paddle towards the ball and serves on its
+/// own
+#[derive(Debug, Clone, Copy)]
+pub struct Ai {
+    side: Side,
+    difficulty: Difficulty,
+    /// Where the center of the paddle should go
+    target: Option<i32>,
+    /// Frames until the next look at the ball
+    timer: u32,
+    waited: u32,
+}
+
+impl Ai {
+    pub fn new(side: Side, difficulty: Difficulty) -> Self {
+        Self {
+            side,
+            difficulty,
+            target: None,
+            timer: 0,
+            waited: 0,
+        }
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Call once per frame before `Pong::next`, returns the motion of the
+    /// paddle in controller steps
+    pub fn next<RND>(&mut self, pong: &mut Pong<RND>) -> i32
+    where
+        RND: FnMut() -> i32,
+    {
+        match pong.status {
+            Status::Serve(server) if server == self.side => {
+                self.waited += 1;
+                if self.waited >= SERVE_DELAY {
+                    self.waited = 0;
+                    pong.serve();
+                }
+            }
+            _ => self.waited = 0,
+        }
+
+        if self.timer == 0 {
+            self.timer = self.difficulty.reaction();
+            self.target = Some(self.aim(pong));
+        } else {
+            self.timer -= 1;
+        }
+
+        let player = match self.side {
+            Side::Player1 => &pong.progress.player1,
+            Side::Player2 => &pong.progress.player2,
+        };
+        let center = player.y + player.height as i32 / 2;
+        let target = self.target.unwrap_or(center);
+        let speed = self.difficulty.speed();
+
+        // positive steps move the paddle up, towards smaller y
+        ((center - target) / pong.paddle_step())
+            .max(-speed)
+            .min(speed)
+    }
+
+    fn aim<RND>(&self, pong: &mut Pong<RND>) -> i32
+    where
+        RND: FnMut() -> i32,
+    {
+        let height = pong.height as i32;
+        let ball = &pong.progress.ball;
+        let player = match self.side {
+            Side::Player1 => &pong.progress.player1,
+            Side::Player2 => &pong.progress.player2,
+        };
+
+        if !ball.moves_towards(player) {
+            return height / 2;
+        }
+
+        let y = if self.difficulty.predicts() {
+            let (x_spd, y_spd) = ball.speed();
+            let frames = (player.x as f32 - ball.x) / x_spd;
+            fold(ball.y + y_spd * frames, height)
+        } else {
+            ball.y as i32
+        };
+
+        let error = player.height as i32 * self.difficulty.error() / 16;
+        let offset = (pong.random)().rem_euclid(2 * error + 1) - error;
+        y + offset
+    }
+}
+
+/// Position on a field of `height` after bouncing off its borders
+fn fold(y: f32, height: i32) -> i32 {
+    if height <= 0 {
+        return 0;
+    }
+
+    let y = (y as i32).rem_euclid(2 * height);
+    if y > height {
+        2 * height - y
+    } else {
+        y
+    }
+}
@@ -1,10 +1,11 @@
-use super::{Player, BALL_MAX_SPEED};
+use super::{environment::Forces, Player, BALL_MAX_SPEED};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Ball {
     pub x: f32,
     pub y: f32,
     pub r: u32,
+    max_spd: f32,
     x_spd: f32,
     y_spd: f32,
 }
@@ -14,31 +15,45 @@ impl Ball {
         Self {
             x: width as f32 / 2.0,
             y: height as f32 / 2.0,
-            r: 3, // TODO: calculate from width and height
+            r: height * 3 / 64,
+            max_spd: Self::max_speed(width),
             x_spd: 0.0,
             y_spd: 0.0,
         }
     }
 
+    pub(crate) fn max_speed(width: u32) -> f32 {
+        BALL_MAX_SPEED * width as f32
+    }
+
     pub(crate) fn with_x_spd(width: u32, height: u32, x_spd: f32) -> Self {
         let mut o = Self::new(width, height);
         o.x_spd = x_spd;
         o
     }
 
-    pub(crate) fn with_rand_x_spd<RND>(width: u32, height: u32, rand: &mut RND) -> Self
-    where
-        RND: FnMut() -> i32,
-    {
-        let rnd = rand();
+    /// Puts the ball in front of the paddle, ready to be served
+    pub(crate) fn hold(&mut self, player: &Player, towards_right: bool) {
+        let r = self.r as f32;
 
-        let ball_x_speed = if rnd % 2 == 0 {
-            BALL_MAX_SPEED
+        self.x = if towards_right {
+            (player.x + player.width as i32) as f32 + r
         } else {
-            -BALL_MAX_SPEED
+            player.x as f32 - r
         };
+        self.y = player.y as f32 + player.height as f32 / 2.0;
+        self.x_spd = 0.0;
+        self.y_spd = 0.0;
+    }
 
-        Self::with_x_spd(width, height, ball_x_speed)
+    pub(crate) fn launch(&mut self, towards_right: bool, y_spd: f32) {
+        self.x_spd = if towards_right {
+            self.max_spd
+        } else {
+            -self.max_spd
+        };
+        self.y_spd = y_spd;
+        self.limit_speed();
     }
 
     pub(crate) fn add_rand_y_spd<RND>(&mut self, rand: &mut RND)
@@ -49,14 +64,14 @@ impl Ball {
         rnd %= 13;
         rnd -= 6;
         let mut rnd = rnd as f32;
-        rnd /= 5.0;
+        rnd *= self.max_spd / 9.0;
 
         self.y_spd += rnd;
         self.limit_speed();
     }
 
     fn limit_speed(&mut self) {
-        let y_limit = BALL_MAX_SPEED / 2.5;
+        let y_limit = self.max_spd / 2.5;
 
         if self.y_spd > y_limit {
             self.y_spd -= self.y_spd - y_limit;
@@ -67,7 +82,7 @@ impl Ball {
         #[cfg(not(any(test, bench)))]
         use micromath::F32Ext;
 
-        let x_spd = (BALL_MAX_SPEED * BALL_MAX_SPEED - self.y_spd * self.y_spd).sqrt();
+        let x_spd = (self.max_spd * self.max_spd - self.y_spd * self.y_spd).sqrt();
         if self.x_spd > 0.0 {
             self.x_spd = x_spd;
         } else {
@@ -75,6 +90,45 @@ impl Ball {
         }
     }
 
+    /// Keeps the ball in a sane range while forces act on it: it must not
+    /// stop or turn around between the paddles, and must not move far enough
+    /// in one frame to pass through a paddle
+    fn clamp_speed(&mut self) {
+        let x_min = self.max_spd / 4.0;
+        let max = self.max_spd * 2.0;
+
+        if self.x_spd < 0.0 {
+            self.x_spd = self.x_spd.min(-x_min).max(-max);
+        } else {
+            self.x_spd = self.x_spd.max(x_min).min(max);
+        }
+        self.y_spd = self.y_spd.max(-max).min(max);
+    }
+
+    pub fn speed(&self) -> (f32, f32) {
+        (self.x_spd, self.y_spd)
+    }
+
+    /// How hard the ball was hit: its speed relative to the regular maximum
+    /// speed, scaled by `pace`, plus how steep it moves
+    pub fn hit_strength(&self, pace: f32) -> f32 {
+        #[cfg(not(any(test, bench)))]
+        use micromath::F32Ext;
+
+        let speed = (self.x_spd * self.x_spd + self.y_spd * self.y_spd).sqrt();
+        let steepness = if self.y_spd < 0.0 {
+            -self.y_spd
+        } else {
+            self.y_spd
+        };
+        (speed * pace + steepness) / self.max_spd
+    }
+
+    pub(crate) fn moves_towards(&self, player: &Player) -> bool {
+        let player_center = player.x as f32 + player.width as f32 / 2.0;
+        (player_center - self.x) * self.x_spd > 0.0
+    }
+
     pub(crate) fn player_collision(&self, player: &Player) -> bool {
         {
             let ball_top = self.y - self.r as f32;
@@ -130,8 +184,36 @@ impl Ball {
         self.y_spd = -self.y_spd;
     }
 
-    pub(crate) fn move_next(&mut self) {
-        self.x += self.x_spd;
-        self.y += self.y_spd;
+    pub(crate) fn keep_inside(&mut self, top_border: u32, bottom_border: u32) {
+        let r = self.r as f32;
+
+        if self.y - r < top_border as f32 {
+            self.y = top_border as f32 + r;
+        } else if self.y + r > bottom_border as f32 {
+            self.y = bottom_border as f32 - r;
+        }
+    }
+
+    pub(crate) fn transposed(&self) -> Self {
+        Self {
+            x: self.y,
+            y: self.x,
+            x_spd: self.y_spd,
+            y_spd: self.x_spd,
+            ..*self
+        }
+    }
+
+    /// `pace` scales the distance travelled in this frame without changing
+    /// the speed itself
+    pub(crate) fn move_next(&mut self, forces: &Forces, pace: f32) {
+        if *forces != Forces::NONE {
+            self.x_spd = (self.x_spd + forces.x) * forces.factor;
+            self.y_spd = (self.y_spd + forces.y) * forces.factor;
+            self.clamp_speed();
+        }
+
+        self.x += self.x_spd * pace;
+        self.y += self.y_spd * pace;
     }
 }
@@ -0,0 +1,171 @@
+use super::{Event, Progress, Side};
+
+pub const MAX_EFFECTS: usize = 4;
+
+/// Number of previous ball positions kept for the trail
+pub const TRAIL_LENGTH: usize = 4;
+
+const FLASH_FRAMES: u32 = 12;
+const PADDLE_FLASH_FRAMES: u32 = 8;
+const SHAKE_FRAMES: u32 = 10;
+
+/// Largest shake offset in pixels
+const SHAKE_AMPLITUDE: i32 = 3;
+
+/// Paddle hits at least this `Ball::hit_strength` shake the screen
+const HARD_HIT: f32 = 1.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectKind {
+    /// The whole screen blinks inverted
+    Flash,
+    /// The paddle of a player blinks
+    PaddleFlash(Side),
+    /// The screen moves around by up to the given number of pixels
+    Shake(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Effect {
+    pub kind: EffectKind,
+    pub frames_left: u32,
+    pub duration: u32,
+}
+
+/// Short visual effects started by game events, advanced once per frame
+#[derive(Debug, Clone, Copy)]
+pub struct Effects {
+    pool: [Option<Effect>; MAX_EFFECTS],
+    trail: [Option<(f32, f32)>; TRAIL_LENGTH],
+    trail_next: usize,
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effects {
+    pub const fn new() -> Self {
+        Self {
+            pool: [None; MAX_EFFECTS],
+            trail: [None; TRAIL_LENGTH],
+            trail_next: 0,
+        }
+    }
+
+    /// Stops all effects and forgets the trail
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Starts an effect, replacing a running effect of the same kind or the
+    /// one closest to its end when the pool is full. An effect of no frames
+    /// isn't started
+    pub fn start(&mut self, kind: EffectKind, frames: u32) {
+        if frames == 0 {
+            return;
+        }
+
+        let effect = Effect {
+            kind,
+            frames_left: frames,
+            duration: frames,
+        };
+
+        let same = self
+            .pool
+            .iter()
+            .position(|slot| matches!(slot, Some(running) if running.kind == kind));
+        let free = self.pool.iter().position(Option::is_none);
+        let oldest = (0..MAX_EFFECTS).min_by_key(|&index| match self.pool[index] {
+            Some(running) => running.frames_left,
+            None => 0,
+        });
+
+        if let Some(index) = same.or(free).or(oldest) {
+            self.pool[index] = Some(effect);
+        }
+    }
+
+    /// Advances all effects by one frame and starts new ones for `event`
+    pub fn update(&mut self, progress: &Progress, event: Option<Event>) {
+        for slot in self.pool.iter_mut() {
+            if let Some(effect) = slot {
+                effect.frames_left -= 1;
+                if effect.frames_left == 0 {
+                    *slot = None;
+                }
+            }
+        }
+
+        self.trail[self.trail_next] = Some((progress.ball.x, progress.ball.y));
+        self.trail_next = (self.trail_next + 1) % TRAIL_LENGTH;
+
+        match event {
+            Some(Event::PaddleHit(side, strength)) => {
+                self.start(EffectKind::PaddleFlash(side), PADDLE_FLASH_FRAMES);
+                if strength >= HARD_HIT {
+                    self.start(EffectKind::Shake(SHAKE_AMPLITUDE), SHAKE_FRAMES);
+                }
+            }
+            Some(Event::Goal(_)) => {
+                self.start(EffectKind::Flash, FLASH_FRAMES);
+                self.trail = [None; TRAIL_LENGTH];
+            }
+            Some(Event::BorderHit) | None => {}
+        }
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Effect> {
+        self.pool.iter().flatten()
+    }
+
+    fn find(&self, kind: EffectKind) -> Option<&Effect> {
+        self.active().find(|effect| effect.kind == kind)
+    }
+
+    /// Whether the screen is drawn inverted in this frame
+    pub fn inverted(&self) -> bool {
+        match self.find(EffectKind::Flash) {
+            Some(effect) => effect.frames_left / 3 % 2 == 0,
+            None => false,
+        }
+    }
+
+    /// Whether the paddle of `side` is highlighted in this frame
+    pub fn paddle_flash(&self, side: Side) -> bool {
+        match self.find(EffectKind::PaddleFlash(side)) {
+            Some(effect) => effect.frames_left / 2 % 2 == 0,
+            None => false,
+        }
+    }
+
+    /// Offset of the whole picture in pixels, fading out over the lifetime
+    /// of the shake
+    pub fn shake(&self) -> (i32, i32) {
+        let shake = self.active().find_map(|effect| match effect.kind {
+            EffectKind::Shake(amplitude) => Some((effect, amplitude)),
+            _ => None,
+        });
+
+        match shake {
+            Some((effect, amplitude)) => {
+                let offset = amplitude * effect.frames_left as i32 / effect.duration as i32;
+                match effect.frames_left % 4 {
+                    0 => (offset, 0),
+                    1 => (0, offset),
+                    2 => (-offset, 0),
+                    _ => (0, -offset),
+                }
+            }
+            None => (0, 0),
+        }
+    }
+
+    /// Previous ball positions in field coordinates, oldest first
+    pub fn trail(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        (0..TRAIL_LENGTH).filter_map(move |age| self.trail[(self.trail_next + age) % TRAIL_LENGTH])
+    }
+}
@@ -0,0 +1,98 @@
+use super::Ball;
+
+pub const MAX_ZONES: usize = 4;
+
+/// Forces acting on the ball. Accelerations are given in field sizes per
+/// frame squared, so they don't depend on the field resolution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Environment {
+    /// Constant acceleration towards the bottom border
+    pub gravity: f32,
+    /// Largest horizontal acceleration the wind can reach
+    pub wind: f32,
+    /// Number of frames between wind changes
+    pub wind_period: u32,
+    pub zones: [Option<Zone>; MAX_ZONES],
+}
+
+/// Part of the field that scales the ball speed every frame the ball is
+/// inside it: `factor` below 1 is a drag zone, above 1 is a boost zone.
+/// Position and size are fractions of the field size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zone {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub factor: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Forces {
+    pub x: f32,
+    pub y: f32,
+    pub factor: f32,
+}
+
+impl Environment {
+    pub const NONE: Self = Self {
+        gravity: 0.0,
+        wind: 0.0,
+        wind_period: 0,
+        zones: [None; MAX_ZONES],
+    };
+
+    pub(crate) fn next_wind<RND>(&self, timer: &mut u32, wind: f32, random: &mut RND) -> f32
+    where
+        RND: FnMut() -> i32,
+    {
+        if self.wind_period == 0 {
+            return self.wind;
+        }
+        if *timer > 0 {
+            *timer -= 1;
+            return wind;
+        }
+
+        *timer = self.wind_period - 1;
+        let rnd = random().rem_euclid(21) - 10;
+        self.wind * rnd as f32 / 10.0
+    }
+
+    pub(crate) fn forces(&self, ball: &Ball, wind: f32, width: u32, height: u32) -> Forces {
+        let (x, y) = (ball.x / width as f32, ball.y / height as f32);
+
+        let factor = self
+            .zones
+            .iter()
+            .flatten()
+            .filter(|zone| zone.contains(x, y))
+            .fold(1.0, |factor, zone| factor * zone.factor);
+
+        Forces {
+            x: wind * width as f32,
+            y: self.gravity * height as f32,
+            factor,
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl Zone {
+    fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+impl Forces {
+    pub const NONE: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        factor: 1.0,
+    };
+}
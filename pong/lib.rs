@@ -1,15 +1,37 @@
 #![cfg_attr(not(any(test, bench)), no_std)]
 
+pub mod ai;
 pub mod ball;
+pub mod effects;
+pub mod environment;
 pub mod player;
 pub mod prelude;
+pub mod viewport;
 
 #[cfg(test)]
 mod test;
 
 pub use prelude::*;
 
-const BALL_MAX_SPEED: f32 = 1.8;
+use environment::{Environment, Forces};
+
+/// Size of the logical field the game is simulated in, independent of the
+/// display it is drawn on
+pub const FIELD_WIDTH: u32 = 1024;
+pub const FIELD_HEIGHT: u32 = 512;
+
+/// Ball speed in field widths per frame
+const BALL_MAX_SPEED: f32 = 1.8 / 128.0;
+
+/// Number of controller steps to move a paddle over the full field height
+pub const PADDLE_STEPS: u32 = 64;
+
+/// Number of controller steps to move a paddle of `paddle_size` percent of
+/// the normal height from the bottom of the field to the top
+pub fn paddle_travel_steps(paddle_size: u32) -> u32 {
+    let paddle = PADDLE_STEPS / 4 * paddle_size / 100;
+    PADDLE_STEPS.saturating_sub(paddle).max(1)
+}
 
 pub struct Pong<RND>
 where
@@ -17,8 +39,20 @@ where
 {
     width: u32,
     height: u32,
+    orientation: Orientation,
+    environment: Environment,
+    wind: f32,
+    wind_timer: u32,
+    balance: Balance,
+    handicaps: (Handicap, Handicap),
+    rules: Rules,
+    first_server: Side,
+    serve_timer: u32,
+    /// How far the paddle of the server moved up in the last frame
+    serve_motion: i32,
     progress: Progress,
     status: Status,
+    event: Option<Event>,
     random: RND,
 }
 
@@ -26,50 +60,266 @@ impl<RND> Pong<RND>
 where
     RND: FnMut() -> i32,
 {
-    pub fn new(width: u32, height: u32, mut random: RND) -> Self {
-        Self {
+    pub fn new(width: u32, height: u32, random: RND) -> Self {
+        Self::with_orientation(width, height, Orientation::Landscape, random)
+    }
+
+    /// `width` and `height` are the size of the field as it is drawn; in
+    /// portrait orientation the ball travels along `height`
+    pub fn with_orientation(
+        width: u32,
+        height: u32,
+        orientation: Orientation,
+        mut random: RND,
+    ) -> Self {
+        let (width, height) = match orientation {
+            Orientation::Landscape => (width, height),
+            Orientation::Portrait => (height, width),
+        };
+
+        let first_server = if random() % 2 == 0 {
+            Side::Player1
+        } else {
+            Side::Player2
+        };
+
+        let mut pong = Self {
             width,
             height,
+            orientation,
+            environment: Environment::NONE,
+            wind: 0.0,
+            wind_timer: 0,
+            balance: Balance::Manual,
+            handicaps: (Handicap::NONE, Handicap::NONE),
+            rules: Rules::default(),
+            first_server,
+            serve_timer: 0,
+            serve_motion: 0,
             status: Status::GameInProgress,
+            event: None,
             progress: Progress {
-                ball: Ball::with_rand_x_spd(width, height, &mut random),
+                ball: Ball::with_x_spd(width, height, 0.0),
                 player1: Player::player1(width, height),
                 player2: Player::player2(width, height),
+                score: (0, 0),
             },
             random,
-        }
+        };
+
+        pong.start_serve(first_server);
+        pong
     }
 
     pub fn reinit(&mut self) {
-        let ball = match self.status {
-            Status::GameOver(last_goal_from) => match last_goal_from {
-                LastGoalFrom::Player1 => Ball::with_x_spd(self.width, self.height, -BALL_MAX_SPEED),
-                LastGoalFrom::Player2 => Ball::with_x_spd(self.width, self.height, BALL_MAX_SPEED),
-            },
-            _ => Ball::with_rand_x_spd(self.width, self.height, &mut self.random),
+        let server = match self.status {
+            Status::GameOver(last_goal_from) => self.server(last_goal_from),
+            Status::MatchOver(winner) => {
+                self.progress.score = (0, 0);
+                if let Balance::Auto = self.balance {
+                    self.balance_handicaps();
+                }
+                winner.other()
+            }
+            Status::Serve(server) => server,
+            Status::GameInProgress => self.first_server,
         };
 
         self.progress = Progress {
-            ball,
+            ball: Ball::with_x_spd(self.width, self.height, 0.0),
             player1: Player::player1(self.width, self.height),
             player2: Player::player2(self.width, self.height),
+            score: self.progress.score,
         };
+        self.apply_handicaps();
+        self.start_serve(server);
+    }
+
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+        self.serve_timer = rules.serve_timeout;
+        self.apply_handicaps();
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Launches the ball from the paddle of the serving player, the motion
+    /// of the paddle in the last frame sets the angle
+    pub fn serve(&mut self) {
+        if let Status::Serve(server) = self.status {
+            self.launch(server, self.serve_motion);
+        }
+    }
+
+    fn server(&self, last_goal_from: LastGoalFrom) -> Side {
+        let scorer = Side::from(last_goal_from);
+
+        match self.rules.serve {
+            ServeRule::Loser => scorer.other(),
+            ServeRule::Winner => scorer,
+            ServeRule::Alternate => {
+                let (score1, score2) = self.progress.score;
+                if (score1 + score2) / 2 % 2 == 0 {
+                    self.first_server
+                } else {
+                    self.first_server.other()
+                }
+            }
+        }
+    }
+
+    /// Frames left until the ball is served without `serve`
+    pub fn serve_countdown(&self) -> Option<u32> {
+        match self.status {
+            Status::Serve(_) if self.serve_timer > 0 => Some(self.serve_timer),
+            _ => None,
+        }
+    }
+
+    fn start_serve(&mut self, server: Side) {
+        self.status = Status::Serve(server);
+        self.serve_timer = self.rules.serve_timeout;
+        self.serve_motion = 0;
+        self.hold_ball(server);
+    }
+
+    fn hold_ball(&mut self, server: Side) {
+        let progress = &mut self.progress;
+        match server {
+            Side::Player1 => progress.ball.hold(&progress.player1, true),
+            Side::Player2 => progress.ball.hold(&progress.player2, false),
+        }
+    }
+
+    /// `motion` is how far the paddle of the server moved up in the last
+    /// frame, it gives the ball a spin in the same direction
+    fn launch(&mut self, server: Side, motion: i32) {
+        let y_spd = -motion as f32 / 2.0;
+        self.progress.ball.launch(server == Side::Player1, y_spd);
         self.status = Status::GameInProgress;
     }
 
+    fn serve_next(&mut self, server: Side, delta1: i32, delta2: i32) -> Result {
+        let before = match server {
+            Side::Player1 => self.progress.player1.y,
+            Side::Player2 => self.progress.player2.y,
+        };
+
+        self.move_player1(delta1);
+        self.move_player2(delta2);
+        self.hold_ball(server);
+
+        let motion = match server {
+            Side::Player1 => before - self.progress.player1.y,
+            Side::Player2 => before - self.progress.player2.y,
+        };
+        self.serve_motion = motion;
+
+        if self.serve_timer > 0 {
+            self.serve_timer -= 1;
+            if self.serve_timer == 0 {
+                self.launch(server, motion);
+            }
+        }
+
+        Result::GameInProgress(self.progress.oriented(self.orientation))
+    }
+
+    pub fn set_balance(&mut self, balance: Balance) {
+        self.balance = balance;
+        if let Balance::Auto = balance {
+            self.balance_handicaps();
+        }
+    }
+
+    pub fn set_handicaps(&mut self, player1: Handicap, player2: Handicap) {
+        self.handicaps = (player1, player2);
+        self.apply_handicaps();
+    }
+
+    fn balance_handicaps(&mut self) {
+        let (score1, score2) = self.progress.score;
+
+        self.handicaps = if score1 > score2 {
+            let lead = score1 - score2;
+            (Handicap::leading(lead), Handicap::trailing(lead))
+        } else {
+            let lead = score2 - score1;
+            (Handicap::trailing(lead), Handicap::leading(lead))
+        };
+        self.apply_handicaps();
+    }
+
+    fn apply_handicaps(&mut self) {
+        let size = self.rules.paddle_size as f32 / 100.0;
+        let sized = |handicap: Handicap| Handicap {
+            paddle_height: handicap.paddle_height * size,
+            ..handicap
+        };
+        let (handicap1, handicap2) = self.handicaps;
+        self.progress
+            .player1
+            .set_handicap(sized(handicap1), self.height);
+        self.progress
+            .player2
+            .set_handicap(sized(handicap2), self.height);
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+        self.wind_timer = 0;
+        self.wind = 0.0;
+    }
+
+    fn forces(&mut self) -> Forces {
+        if self.environment == Environment::NONE {
+            return Forces::NONE;
+        }
+
+        self.wind = self
+            .environment
+            .next_wind(&mut self.wind_timer, self.wind, &mut self.random);
+
+        self.environment
+            .forces(&self.progress.ball, self.wind, self.width, self.height)
+    }
+
     fn move_ball(&mut self) -> Result {
+        let forces = self.forces();
         let ball = &mut self.progress.ball;
+        let player1 = &self.progress.player1;
+        let player2 = &self.progress.player2;
 
-        ball.move_next();
+        let pace = if ball.moves_towards(player1) {
+            player1.handicap.ball_speed
+        } else {
+            player2.handicap.ball_speed
+        };
+        ball.move_next(&forces, pace * self.rules.ball_speed as f32 / 100.0);
+
+        let hit = if ball.player_collision(player1) && ball.moves_towards(player1) {
+            Some(Side::Player1)
+        } else if ball.player_collision(player2) && ball.moves_towards(player2) {
+            Some(Side::Player2)
+        } else {
+            None
+        };
 
-        if ball.player_collision(&self.progress.player1)
-            || ball.player_collision(&self.progress.player2)
-        {
+        if let Some(side) = hit {
             ball.bounce_off_player(&mut self.random);
+            let pace = match side {
+                Side::Player1 => player2.handicap.ball_speed,
+                Side::Player2 => player1.handicap.ball_speed,
+            };
+            self.event = Some(Event::PaddleHit(side, ball.hit_strength(pace)));
         }
 
         if ball.border_collision(0, self.height) {
             ball.bounce_off_border();
+            ball.keep_inside(0, self.height);
+            self.event = self.event.or(Some(Event::BorderHit));
         }
 
         if ball.x < 0f32 {
@@ -80,15 +330,40 @@ where
             return self.game_over(LastGoalFrom::Player1);
         }
 
-        Result::GameInProgress(self.progress)
+        Result::GameInProgress(self.progress.oriented(self.orientation))
     }
 
     fn game_over(&mut self, last_goal_from: LastGoalFrom) -> Result {
-        self.status = Status::GameOver(last_goal_from);
+        match last_goal_from {
+            LastGoalFrom::Player1 => self.progress.score.0 += 1,
+            LastGoalFrom::Player2 => self.progress.score.1 += 1,
+        }
+        if let Balance::Auto = self.balance {
+            self.balance_handicaps();
+        }
+
+        let (score1, score2) = self.progress.score;
+        let win_score = self.rules.win_score;
+        self.event = Some(Event::Goal(last_goal_from));
+        self.status = if win_score > 0 && score1 >= win_score {
+            Status::MatchOver(Side::Player1)
+        } else if win_score > 0 && score2 >= win_score {
+            Status::MatchOver(Side::Player2)
+        } else {
+            Status::GameOver(last_goal_from)
+        };
         Result::GameOver(last_goal_from)
     }
 
+    fn paddle_step(&self) -> i32 {
+        (self.height / PADDLE_STEPS).max(1) as i32
+    }
+
     fn move_player1(&mut self, delta: i32) {
+        let delta = self
+            .progress
+            .player1
+            .scale_delta(delta * self.paddle_step());
         if delta > 0 {
             self.progress.player1.move_up(delta, 0);
         } else if delta < 0 {
@@ -97,6 +372,10 @@ where
     }
 
     fn move_player2(&mut self, delta: i32) {
+        let delta = self
+            .progress
+            .player2
+            .scale_delta(delta * self.paddle_step());
         if delta > 0 {
             self.progress.player2.move_up(delta, 0);
         } else if delta < 0 {
@@ -105,16 +384,40 @@ where
     }
 
     pub fn next(&mut self, delta1: i32, delta2: i32) -> Result {
-        if let Status::GameInProgress = self.status {
-            self.move_player1(delta1);
-            self.move_player2(delta2);
-            self.move_ball()
-        } else {
-            Result::Err
+        self.event = None;
+        match self.status {
+            Status::GameInProgress => {
+                self.move_player1(delta1);
+                self.move_player2(delta2);
+                self.move_ball()
+            }
+            Status::Serve(server) => self.serve_next(server, delta1, delta2),
+            Status::GameOver(_) | Status::MatchOver(_) => Result::Err,
         }
     }
 
     pub fn status(&self) -> Status {
         self.status
     }
+
+    /// What happened in the last frame
+    pub fn event(&self) -> Option<Event> {
+        self.event
+    }
+
+    pub fn score(&self) -> (u32, u32) {
+        self.progress.score
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Size of the field as it is drawn
+    pub fn size(&self) -> (u32, u32) {
+        match self.orientation {
+            Orientation::Landscape => (self.width, self.height),
+            Orientation::Portrait => (self.height, self.width),
+        }
+    }
 }
@@ -4,27 +4,110 @@ pub struct Player {
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    pub handicap: Handicap,
+    residue: f32,
+}
+
+/// Multipliers that weaken or strengthen a player, 1.0 means no handicap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Handicap {
+    pub paddle_height: f32,
+    pub paddle_speed: f32,
+    /// Applied while the ball moves towards the player
+    pub ball_speed: f32,
+}
+
+impl Handicap {
+    pub const NONE: Self = Self {
+        paddle_height: 1.0,
+        paddle_speed: 1.0,
+        ball_speed: 1.0,
+    };
+
+    /// Handicap of a player who leads by `lead` points
+    pub(super) fn leading(lead: u32) -> Self {
+        let lead = lead as f32;
+        Self {
+            paddle_height: 1.0 - 0.1 * lead,
+            paddle_speed: 1.0 - 0.05 * lead,
+            ball_speed: 1.0,
+        }
+        .limited()
+    }
+
+    /// Handicap of a player who is behind by `lead` points
+    pub(super) fn trailing(lead: u32) -> Self {
+        Self {
+            paddle_height: 1.0,
+            paddle_speed: 1.0,
+            ball_speed: 1.0 - 0.05 * lead as f32,
+        }
+        .limited()
+    }
+
+    fn limited(self) -> Self {
+        let limit = |value: f32, min: f32| value.max(min).min(2.0);
+        Self {
+            paddle_height: limit(self.paddle_height, 0.5),
+            paddle_speed: limit(self.paddle_speed, 0.5),
+            ball_speed: limit(self.ball_speed, 0.7),
+        }
+    }
+}
+
+impl Default for Handicap {
+    fn default() -> Self {
+        Self::NONE
+    }
 }
 
 impl Player {
-    pub(super) fn player1(_width: u32, height: u32) -> Self {
+    fn thickness(width: u32) -> u32 {
+        (width / 64).max(2)
+    }
+
+    pub(super) fn player1(width: u32, height: u32) -> Self {
         Self {
             x: 0,
             y: (3 * height / 8) as i32,
-            width: 2,
-            height: (height / 4) as u32,
+            width: Self::thickness(width),
+            height: height / 4,
+            handicap: Handicap::NONE,
+            residue: 0.0,
         }
     }
 
     pub(super) fn player2(width: u32, height: u32) -> Self {
         Self {
-            x: (width - 2) as i32,
+            x: (width - Self::thickness(width)) as i32,
             y: (3 * height / 8) as i32,
-            width: 2,
-            height: (height / 4) as u32,
+            width: Self::thickness(width),
+            height: height / 4,
+            handicap: Handicap::NONE,
+            residue: 0.0,
         }
     }
 
+    pub(super) fn set_handicap(&mut self, handicap: Handicap, field_height: u32) {
+        let handicap = handicap.limited();
+        let center = self.y + self.height as i32 / 2;
+
+        self.handicap = handicap;
+        self.height = ((field_height / 4) as f32 * handicap.paddle_height) as u32;
+        self.height = self.height.min(field_height);
+        self.y = center - self.height as i32 / 2;
+        self.y = self.y.max(0).min((field_height - self.height) as i32);
+    }
+
+    /// Scales a movement by the paddle speed handicap, keeping the fractional
+    /// part for the next frame
+    pub(super) fn scale_delta(&mut self, delta: i32) -> i32 {
+        let delta = delta as f32 * self.handicap.paddle_speed + self.residue;
+        let scaled = delta as i32;
+        self.residue = delta - scaled as f32;
+        scaled
+    }
+
     pub(super) fn move_up(&mut self, up: i32, up_limit: u32) {
         self.y -= up;
         if self.y < up_limit as i32 {
@@ -39,4 +122,14 @@ impl Player {
             self.y = (down_limit - self.height) as i32;
         }
     }
+
+    pub(super) fn transposed(&self) -> Self {
+        Self {
+            x: self.y,
+            y: self.x,
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
 }
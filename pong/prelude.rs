@@ -1,15 +1,110 @@
-pub use super::{ball::Ball, player::Player};
+use numtoa::NumToA;
+
+pub use super::{
+    ball::Ball,
+    player::{Handicap, Player},
+};
 
 #[derive(Clone, Copy)]
 pub struct Progress {
     pub ball: Ball,
     pub player1: Player,
     pub player2: Player,
+    pub score: (u32, u32),
+}
+
+impl Progress {
+    pub(crate) fn oriented(self, orientation: Orientation) -> Self {
+        match orientation {
+            Orientation::Landscape => self,
+            Orientation::Portrait => Self {
+                ball: self.ball.transposed(),
+                player1: self.player1.transposed(),
+                player2: self.player2.transposed(),
+                ..self
+            },
+        }
+    }
 }
 
 pub trait Drawer {
+    fn begin_frame(&mut self);
+    fn end_frame(&mut self);
+
     fn draw_ball(&mut self, ball: &Ball);
     fn draw_player(&mut self, player: &Player);
+    fn draw_score(&mut self, score: &(u32, u32));
+
+    fn draw_net(&mut self) {}
+    fn draw_borders(&mut self) {}
+
+    fn draw_text(&mut self, text: &str, position: TextPosition);
+    fn draw_screen(&mut self, screen: &Screen);
+
+    fn draw_overlay(&mut self, overlay: &Overlay) {
+        match overlay {
+            Overlay::Countdown(seconds) => {
+                let mut buffer = [0u8; 10];
+                self.draw_text(seconds.numtoa_str(10, &mut buffer), TextPosition::Center);
+            }
+            Overlay::Paused => self.draw_text("PAUSE", TextPosition::Center),
+        }
+    }
+}
+
+fn draw_field(progress: &Progress, drawer: &mut impl Drawer) {
+    drawer.draw_borders();
+    drawer.draw_net();
+    drawer.draw_score(&progress.score);
+    drawer.draw_ball(&progress.ball);
+    drawer.draw_player(&progress.player1);
+    drawer.draw_player(&progress.player2);
+}
+
+/// Draws a whole frame of the game
+pub fn render(progress: &Progress, drawer: &mut impl Drawer) {
+    drawer.begin_frame();
+    draw_field(progress, drawer);
+    drawer.end_frame();
+}
+
+/// Draws a whole frame of the game with an overlay over the field
+pub fn render_overlay(progress: &Progress, overlay: &Overlay, drawer: &mut impl Drawer) {
+    drawer.begin_frame();
+    draw_field(progress, drawer);
+    drawer.draw_overlay(overlay);
+    drawer.end_frame();
+}
+
+/// Draws a whole frame with a screen instead of the field
+pub fn render_screen(screen: &Screen, drawer: &mut impl Drawer) {
+    drawer.begin_frame();
+    drawer.draw_screen(screen);
+    drawer.end_frame();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextPosition {
+    Top,
+    Center,
+    Bottom,
+}
+
+#[derive(Clone, Copy)]
+pub enum Screen {
+    /// Shown after power on
+    Splash,
+    /// Shown after a goal until a player continues the game
+    Goal(Side, (u32, u32)),
+    /// Shown when a player has won the match, with the final score
+    MatchOver(Side, (u32, u32)),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    /// Seconds left until the ball is served
+    Countdown(u32),
+    Paused,
 }
 
 #[derive(Clone, Copy)]
@@ -22,11 +117,102 @@ pub enum Result {
 #[derive(Clone, Copy)]
 pub enum Status {
     GameOver(LastGoalFrom),
+    /// A player reached `Rules::win_score`, `Pong::reinit` starts a new
+    /// match
+    MatchOver(Side),
+    /// The ball sits on the paddle of the serving player
+    Serve(Side),
     GameInProgress,
 }
 
-#[derive(Clone, Copy)]
+/// Something that happened in the last frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// The ball bounced off the paddle of a player, with
+    /// `Ball::hit_strength` after the bounce
+    PaddleHit(Side, f32),
+    BorderHit,
+    Goal(LastGoalFrom),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LastGoalFrom {
     Player1,
     Player2,
 }
+
+impl From<LastGoalFrom> for Side {
+    fn from(scorer: LastGoalFrom) -> Self {
+        match scorer {
+            LastGoalFrom::Player1 => Side::Player1,
+            LastGoalFrom::Player2 => Side::Player2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Paddles on the left and right edges of the field
+    Landscape,
+    /// Paddles on the top and bottom edges of the field
+    Portrait,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Balance {
+    /// Handicaps are only changed with `Pong::set_handicaps`
+    Manual,
+    /// Handicaps follow the score difference after every goal
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Player1,
+    Player2,
+}
+
+impl Side {
+    pub fn other(self) -> Self {
+        match self {
+            Side::Player1 => Side::Player2,
+            Side::Player2 => Side::Player1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServeRule {
+    /// The player who conceded the last goal serves
+    Loser,
+    /// The player who scored the last goal serves
+    Winner,
+    /// Players take turns serving every two points
+    Alternate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub serve: ServeRule,
+    /// Frames before the ball is served without `Pong::serve`, 0 to wait
+    /// forever
+    pub serve_timeout: u32,
+    /// Points needed to win the match, 0 to play forever
+    pub win_score: u32,
+    /// Percent of the normal ball speed
+    pub ball_speed: u32,
+    /// Percent of the normal paddle height, handicaps apply on top
+    pub paddle_size: u32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            serve: ServeRule::Loser,
+            serve_timeout: 180,
+            win_score: 11,
+            ball_speed: 100,
+            paddle_size: 100,
+        }
+    }
+}
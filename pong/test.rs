@@ -20,7 +20,13 @@ macro_rules! add_test {
     };
 }
 
-use crate::{Ball, Player};
+use crate::{
+    ai::Ai,
+    effects::{EffectKind, Effects},
+    environment::{Environment, Zone},
+    viewport::Viewport,
+    Ball, LastGoalFrom, Orientation, Player, Pong, Result, Rules, ServeRule, Side, Status,
+};
 
 fn new_ball(x: i32, y: i32) -> Ball {
     let mut ball = Ball::with_x_spd(64, 64, 0.0);
@@ -63,6 +69,439 @@ fn border_collision(bx: i32, by: i32, top_border: u32, bottom_border: u32, expec
     );
 }
 
+fn orientation(width: u32, height: u32, orientation: Orientation) {
+    let mut pong = Pong::with_orientation(width, height, orientation, || 0);
+    pong.serve();
+
+    let progress = match pong.next(0, 0) {
+        Result::GameInProgress(progress) => progress,
+        _ => panic!("game is not in progress"),
+    };
+    let (player1, player2) = (progress.player1, progress.player2);
+
+    match orientation {
+        Orientation::Landscape => {
+            assert_eq!(player1.x, 0);
+            assert_eq!(player2.x + player2.width as i32, width as i32);
+            assert!(player1.height > player1.width);
+        }
+        Orientation::Portrait => {
+            assert_eq!(player1.y, 0);
+            assert_eq!(player2.y + player2.height as i32, height as i32);
+            assert!(player1.width > player1.height);
+        }
+    }
+
+    let ball = progress.ball;
+    let (x_spd, y_spd) = ball.speed();
+    let (served_from, paddle) = match orientation {
+        Orientation::Landscape => {
+            assert!(x_spd > 0.0);
+            (ball.x - x_spd, player1.x + player1.width as i32)
+        }
+        Orientation::Portrait => {
+            assert!(y_spd > 0.0);
+            (ball.y - y_spd, player1.y + player1.height as i32)
+        }
+    };
+    assert!((served_from - ball.r as f32 - paddle as f32).abs() < 0.01);
+}
+
+fn viewport(width: u32, height: u32, x: i32, y: i32, field_width: u32, field_height: u32) {
+    let field = (crate::FIELD_WIDTH, crate::FIELD_HEIGHT);
+    let viewport = Viewport::fit(field, (0, 0), (width, height));
+
+    assert_eq!(viewport.field(field), ((x, y), (field_width, field_height)));
+    assert_eq!(
+        viewport.point(field.0 as f32, field.1 as f32),
+        (x + field_width as i32, y + field_height as i32)
+    );
+}
+
+fn environment(gravity: f32, wind: f32, factor: f32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 7);
+    let mut zones = [None; crate::environment::MAX_ZONES];
+    zones[0] = Some(Zone {
+        x: 0.25,
+        y: 0.0,
+        width: 0.5,
+        height: 1.0,
+        factor,
+    });
+    pong.set_environment(Environment {
+        gravity,
+        wind,
+        wind_period: 30,
+        zones,
+    });
+
+    pong.serve();
+
+    let (mut x_spd, _) = pong.progress.ball.speed();
+    for _ in 0..1000 {
+        let follow = |player: &Player, ball: &Ball| {
+            let center = player.y as f32 + player.height as f32 / 2.0;
+            ((center - ball.y) / 8.0).clamp(-4.0, 4.0) as i32
+        };
+        let delta1 = follow(&pong.progress.player1, &pong.progress.ball);
+        let delta2 = follow(&pong.progress.player2, &pong.progress.ball);
+
+        let ball = match pong.next(delta1, delta2) {
+            Result::GameInProgress(progress) => progress.ball,
+            Result::GameOver(_) => panic!("paddles follow the ball and must not miss it"),
+            Result::Err => panic!("game is not in progress"),
+        };
+        let (next_x_spd, y_spd) = ball.speed();
+
+        assert!(ball.y - ball.r as f32 >= 0.0);
+        assert!(ball.y + ball.r as f32 <= crate::FIELD_HEIGHT as f32);
+        assert!(next_x_spd != 0.0);
+        assert!(next_x_spd.abs() < (2 * ball.r + pong.progress.player1.width) as f32);
+        assert!(y_spd.abs() < crate::FIELD_HEIGHT as f32 / 2.0);
+
+        let bounced = (next_x_spd > 0.0) != (x_spd > 0.0);
+        if bounced {
+            let near_player1 = ball.x < crate::FIELD_WIDTH as f32 / 4.0;
+            let near_player2 = ball.x > 3.0 * crate::FIELD_WIDTH as f32 / 4.0;
+            assert!(near_player1 || near_player2);
+        }
+        x_spd = next_x_spd;
+    }
+}
+
+fn balance(score1: u32, score2: u32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    let normal = pong.progress.player1;
+
+    pong.progress.score = (score1, score2);
+    pong.set_balance(crate::Balance::Auto);
+    pong.reinit();
+
+    let (leader, trailer) = if score1 > score2 {
+        (pong.progress.player1, pong.progress.player2)
+    } else {
+        (pong.progress.player2, pong.progress.player1)
+    };
+
+    if score1 == score2 {
+        assert_eq!(leader.handicap, crate::Handicap::NONE);
+        assert_eq!(trailer.handicap, crate::Handicap::NONE);
+        assert_eq!(leader.height, normal.height);
+        return;
+    }
+
+    assert!(leader.height < normal.height);
+    assert!(leader.handicap.paddle_speed < 1.0);
+    assert_eq!(leader.handicap.ball_speed, 1.0);
+    assert_eq!(trailer.height, normal.height);
+    assert!(trailer.handicap.ball_speed < 1.0);
+    assert!(leader.y >= 0 && leader.y as u32 + leader.height <= crate::FIELD_HEIGHT);
+}
+
+fn paddle_speed(multiplier: f32, delta: i32, frames: i32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    let handicap = crate::Handicap {
+        paddle_speed: multiplier,
+        ..crate::Handicap::NONE
+    };
+    pong.set_handicaps(handicap, crate::Handicap::NONE);
+
+    let start = (pong.progress.player1.y, pong.progress.player2.y);
+    for _ in 0..frames {
+        pong.move_player1(delta);
+        pong.move_player2(delta);
+    }
+    let moved1 = start.0 - pong.progress.player1.y;
+    let moved2 = start.1 - pong.progress.player2.y;
+
+    assert_eq!(moved1, (moved2 as f32 * multiplier) as i32);
+}
+
+fn serve_rule(rule: ServeRule, goals: &[LastGoalFrom], expect: Side) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        serve: rule,
+        ..Rules::default()
+    });
+
+    for goal in goals {
+        pong.game_over(*goal);
+        pong.reinit();
+    }
+
+    match pong.status() {
+        Status::Serve(server) => assert_eq!(server, expect),
+        _ => panic!("ball must wait for a serve"),
+    }
+}
+
+fn serve_timeout(timeout: u32, delta: i32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 1);
+    pong.set_rules(Rules {
+        serve_timeout: timeout,
+        ..Rules::default()
+    });
+    pong.reinit();
+
+    for _ in 1..timeout {
+        let progress = match pong.next(delta, delta) {
+            Result::GameInProgress(progress) => progress,
+            _ => panic!("game is not in progress"),
+        };
+        let paddle = progress.player2;
+        assert_eq!(progress.ball.speed(), (0.0, 0.0));
+        assert_eq!(progress.ball.x, paddle.x as f32 - progress.ball.r as f32);
+        assert_eq!(
+            progress.ball.y,
+            paddle.y as f32 + paddle.height as f32 / 2.0
+        );
+    }
+
+    pong.next(delta, delta);
+    assert!(matches!(pong.status(), Status::GameInProgress));
+
+    let (x_spd, y_spd) = pong.progress.ball.speed();
+    assert!(x_spd < 0.0);
+    assert_eq!(y_spd < 0.0, delta > 0);
+    assert_eq!(y_spd > 0.0, delta < 0);
+}
+
+fn key_serve(delta: i32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 1);
+    pong.next(delta, delta);
+    pong.serve();
+    assert!(matches!(pong.status(), Status::GameInProgress));
+
+    let (x_spd, y_spd) = pong.progress.ball.speed();
+    assert!(x_spd < 0.0);
+    assert_eq!(y_spd < 0.0, delta > 0);
+    assert_eq!(y_spd > 0.0, delta < 0);
+}
+
+#[derive(Default)]
+struct RecordingDrawer {
+    calls: Vec<&'static str>,
+}
+
+impl crate::Drawer for RecordingDrawer {
+    fn begin_frame(&mut self) {
+        self.calls.push("begin_frame");
+    }
+    fn end_frame(&mut self) {
+        self.calls.push("end_frame");
+    }
+    fn draw_ball(&mut self, _: &Ball) {
+        self.calls.push("draw_ball");
+    }
+    fn draw_player(&mut self, _: &Player) {
+        self.calls.push("draw_player");
+    }
+    fn draw_score(&mut self, _: &(u32, u32)) {
+        self.calls.push("draw_score");
+    }
+    fn draw_text(&mut self, _: &str, _: crate::TextPosition) {
+        self.calls.push("draw_text");
+    }
+    fn draw_screen(&mut self, _: &crate::Screen) {
+        self.calls.push("draw_screen");
+    }
+}
+
+fn render(screen: bool, overlay: Option<crate::Overlay>, expect: &[&str]) {
+    let pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    let mut drawer = RecordingDrawer::default();
+
+    if screen {
+        let screen = crate::Screen::Goal(Side::Player1, pong.score());
+        crate::render_screen(&screen, &mut drawer);
+    } else if let Some(overlay) = overlay {
+        crate::render_overlay(&pong.progress, &overlay, &mut drawer);
+    } else {
+        crate::render(&pong.progress, &mut drawer);
+    }
+
+    assert_eq!(drawer.calls, expect);
+}
+
+fn match_over(win_score: u32, goals: &[LastGoalFrom], winner: Option<Side>) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        win_score,
+        ..Rules::default()
+    });
+
+    for goal in goals {
+        pong.reinit();
+        pong.game_over(*goal);
+    }
+
+    match (pong.status(), winner) {
+        (Status::MatchOver(side), Some(winner)) => {
+            assert_eq!(side, winner);
+            pong.reinit();
+            assert_eq!(pong.score(), (0, 0));
+            assert!(matches!(pong.status(), Status::Serve(server) if server == winner.other()));
+        }
+        (Status::GameOver(_), None) => {}
+        _ => panic!("wrong winner"),
+    }
+}
+
+fn serve_countdown(timeout: u32, frames: u32, expect: Option<u32>) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        serve_timeout: timeout,
+        ..Rules::default()
+    });
+    pong.reinit();
+
+    for _ in 0..frames {
+        pong.next(0, 0);
+    }
+
+    assert_eq!(pong.serve_countdown(), expect);
+}
+
+fn paddle_size(size: u32, height: u32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        paddle_size: size,
+        ..Rules::default()
+    });
+
+    for player in [pong.progress.player1, pong.progress.player2] {
+        assert_eq!(player.height, height);
+        assert!(player.y >= 0 && player.y as u32 + player.height <= crate::FIELD_HEIGHT);
+    }
+
+    let step = crate::FIELD_HEIGHT / crate::PADDLE_STEPS;
+    assert_eq!(
+        crate::paddle_travel_steps(size) * step,
+        crate::FIELD_HEIGHT - height
+    );
+}
+
+fn ball_speed(speed: u32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        ball_speed: speed,
+        ..Rules::default()
+    });
+    pong.serve();
+
+    let start = pong.progress.ball.x;
+    pong.next(0, 0);
+    let (x_spd, _) = pong.progress.ball.speed();
+
+    let moved = pong.progress.ball.x - start;
+    assert!((moved - x_spd * speed as f32 / 100.0).abs() < 0.001);
+}
+
+fn ai_serves(difficulty: crate::ai::Difficulty) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    pong.set_rules(Rules {
+        serve_timeout: 0,
+        ..Rules::default()
+    });
+    pong.reinit();
+    let mut ai = Ai::new(Side::Player1, difficulty);
+
+    for _ in 1..60 {
+        let delta = ai.next(&mut pong);
+        pong.next(delta, 0);
+        assert!(matches!(pong.status(), Status::Serve(Side::Player1)));
+    }
+
+    let delta = ai.next(&mut pong);
+    pong.next(delta, 0);
+    assert!(matches!(pong.status(), Status::GameInProgress));
+}
+
+/// The ball is served from the top of the field straight towards the
+/// computer, which has to get within `tolerance` of it
+fn ai_tracks(difficulty: crate::ai::Difficulty, tolerance: i32) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 6);
+    for _ in 0..10 {
+        pong.next(5, 0);
+    }
+    pong.serve();
+    let mut ai = Ai::new(Side::Player2, difficulty);
+
+    for _ in 0..50 {
+        let delta = ai.next(&mut pong);
+        pong.next(0, delta);
+    }
+
+    let player = pong.progress.player2;
+    let center = player.y + player.height as i32 / 2;
+    assert!(matches!(pong.status(), Status::GameInProgress));
+    assert!((center - pong.progress.ball.y as i32).abs() <= tolerance);
+}
+
+fn paddle_hit(steep: bool, shake: bool) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 6);
+    pong.serve();
+    if steep {
+        pong.progress.ball.launch(true, crate::FIELD_HEIGHT as f32);
+    }
+
+    let mut effects = Effects::new();
+    let mut hit = None;
+    for _ in 0..1000 {
+        let player2 = &mut pong.progress.player2;
+        player2.y = pong.progress.ball.y as i32 - player2.height as i32 / 2;
+
+        pong.next(0, 0);
+        let progress = pong.progress;
+        effects.update(&progress, pong.event());
+
+        if let Some(crate::Event::PaddleHit(side, strength)) = pong.event() {
+            hit = Some((side, strength));
+            break;
+        }
+    }
+
+    let (side, strength) = hit.expect("ball must hit the paddle");
+    assert_eq!(side, Side::Player2);
+    assert!(strength >= 1.0);
+    assert!(effects.paddle_flash(Side::Player2));
+    assert!(!effects.paddle_flash(Side::Player1));
+    assert_eq!(effects.shake() != (0, 0), shake);
+    assert_eq!(effects.trail().count(), crate::effects::TRAIL_LENGTH);
+}
+
+fn effects(kinds: &[EffectKind], frames: u32, updates: u32, active: usize) {
+    let pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    let mut effects = Effects::new();
+
+    for kind in kinds {
+        effects.start(*kind, frames);
+    }
+    for _ in 0..updates {
+        effects.update(&pong.progress, None);
+    }
+
+    assert_eq!(effects.active().count(), active);
+    assert!(effects
+        .active()
+        .all(|effect| effect.frames_left == frames - updates));
+}
+
+fn goal_flash(updates: u32, inverted: bool) {
+    let mut pong = Pong::new(crate::FIELD_WIDTH, crate::FIELD_HEIGHT, || 0);
+    let mut effects = Effects::new();
+
+    effects.update(&pong.progress, None);
+    pong.game_over(LastGoalFrom::Player1);
+    effects.update(&pong.progress, pong.event());
+    assert_eq!(effects.trail().count(), 0);
+
+    for _ in 0..updates {
+        effects.update(&pong.progress, None);
+    }
+    assert_eq!(effects.inverted(), inverted);
+}
+
 add_test! {
     player_collision {
         same(0, 0, 0, 0, true),
@@ -91,4 +530,146 @@ add_test! {
         collision_top(0, 3, 0, 64, true),
         collision_bottom(0, 61, 0, 64, true),
     },
+    orientation {
+        landscape(128, 64, crate::Orientation::Landscape),
+        portrait(64, 128, crate::Orientation::Portrait),
+    },
+    viewport {
+        exact(128, 64, 0, 0, 128, 64),
+        pillarbox(128, 32, 32, 0, 64, 32),
+        letterbox(64, 48, 0, 8, 64, 32),
+        small(96, 16, 32, 0, 32, 16),
+    },
+    environment {
+        none(0.0, 0.0, 1.0),
+        gravity(0.0005, 0.0, 1.0),
+        wind(0.0, 0.0002, 1.0),
+        drag(0.0, 0.0, 0.9),
+        boost(0.0005, 0.0002, 1.1),
+    },
+    balance {
+        even(3, 3),
+        player1_leads(5, 1),
+        player2_leads(0, 4),
+        runaway(20, 0),
+    },
+    paddle_speed {
+        slow(0.5, 1, 10),
+        fractional(0.75, 1, 20),
+        fast(1.5, -1, 10),
+    },
+    serve_rule {
+        first(crate::ServeRule::Loser, &[], crate::Side::Player1),
+        loser(crate::ServeRule::Loser, &[crate::LastGoalFrom::Player1], crate::Side::Player2),
+        winner(crate::ServeRule::Winner, &[crate::LastGoalFrom::Player1], crate::Side::Player1),
+        alternate_1(crate::ServeRule::Alternate, &[crate::LastGoalFrom::Player2], crate::Side::Player1),
+        alternate_2(
+            crate::ServeRule::Alternate,
+            &[crate::LastGoalFrom::Player2, crate::LastGoalFrom::Player2],
+            crate::Side::Player2,
+        ),
+        alternate_4(
+            crate::ServeRule::Alternate,
+            &[crate::LastGoalFrom::Player2, crate::LastGoalFrom::Player1, crate::LastGoalFrom::Player1, crate::LastGoalFrom::Player2],
+            crate::Side::Player1,
+        ),
+    },
+    serve_timeout {
+        still(10, 0),
+        up(10, 1),
+        down(10, -1),
+    },
+    key_serve {
+        still(0),
+        up(1),
+        down(-1),
+    },
+    render {
+        frame(
+            false,
+            None,
+            &[
+                "begin_frame",
+                "draw_score",
+                "draw_ball",
+                "draw_player",
+                "draw_player",
+                "end_frame",
+            ],
+        ),
+        overlay(
+            false,
+            Some(crate::Overlay::Paused),
+            &[
+                "begin_frame",
+                "draw_score",
+                "draw_ball",
+                "draw_player",
+                "draw_player",
+                "draw_text",
+                "end_frame",
+            ],
+        ),
+        screen(true, None, &["begin_frame", "draw_screen", "end_frame"]),
+    },
+    match_over {
+        playing(3, &[crate::LastGoalFrom::Player1, crate::LastGoalFrom::Player1], None),
+        player1(2, &[crate::LastGoalFrom::Player2, crate::LastGoalFrom::Player1, crate::LastGoalFrom::Player1], Some(crate::Side::Player1)),
+        player2(1, &[crate::LastGoalFrom::Player2], Some(crate::Side::Player2)),
+        endless(0, &[crate::LastGoalFrom::Player1; 20], None),
+    },
+    serve_countdown {
+        start(180, 0, Some(180)),
+        running(180, 60, Some(120)),
+        served(10, 10, None),
+        manual(0, 5, None),
+    },
+    paddle_size {
+        normal(100, crate::FIELD_HEIGHT / 4),
+        small(50, crate::FIELD_HEIGHT / 8),
+        large(200, crate::FIELD_HEIGHT / 2),
+    },
+    ball_speed {
+        slow(50),
+        normal(100),
+        fast(150),
+    },
+    ai_serves {
+        easy(crate::ai::Difficulty::Easy),
+        hard(crate::ai::Difficulty::Hard),
+    },
+    ai_tracks {
+        easy(crate::ai::Difficulty::Easy, 104),
+        normal(crate::ai::Difficulty::Normal, 56),
+        hard(crate::ai::Difficulty::Hard, 24),
+    },
+    paddle_hit {
+        flat(false, false),
+        steep(true, true),
+    },
+    effects {
+        one(&[crate::effects::EffectKind::Flash], 5, 2, 1),
+        expired(&[crate::effects::EffectKind::Flash], 5, 5, 0),
+        no_frames(&[crate::effects::EffectKind::Shake(2)], 0, 1, 0),
+        same_kind(&[crate::effects::EffectKind::Shake(2), crate::effects::EffectKind::Shake(2)], 5, 1, 1),
+        full(
+            &[
+                crate::effects::EffectKind::Flash,
+                crate::effects::EffectKind::PaddleFlash(crate::Side::Player1),
+                crate::effects::EffectKind::PaddleFlash(crate::Side::Player2),
+                crate::effects::EffectKind::Shake(1),
+                crate::effects::EffectKind::Shake(2),
+                crate::effects::EffectKind::Shake(3),
+            ],
+            8,
+            3,
+            crate::effects::MAX_EFFECTS,
+        ),
+    },
+    goal_flash {
+        start(0, true),
+        blink(3, false),
+        again(6, true),
+        over(20, false),
+    },
 }
@@ -0,0 +1,77 @@
+use super::{Ball, Player};
+
+/// Maps field coordinates onto a screen area, keeping the aspect ratio of the
+/// field and centering it inside the area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    x: i32,
+    y: i32,
+    num: u32,
+    den: u32,
+}
+
+impl Viewport {
+    pub fn fit(field: (u32, u32), origin: (i32, i32), area: (u32, u32)) -> Self {
+        let (field_width, field_height) = field;
+        let (area_width, area_height) = area;
+
+        let (num, den) = if area_width * field_height <= area_height * field_width {
+            (area_width, field_width)
+        } else {
+            (area_height, field_height)
+        };
+
+        let width = field_width * num / den;
+        let height = field_height * num / den;
+
+        Self {
+            x: origin.0 + ((area_width - width) / 2) as i32,
+            y: origin.1 + ((area_height - height) / 2) as i32,
+            num,
+            den,
+        }
+    }
+
+    /// Same mapping moved by a number of pixels
+    pub fn shifted(&self, dx: i32, dy: i32) -> Self {
+        Self {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..*self
+        }
+    }
+
+    pub fn length(&self, length: u32) -> u32 {
+        if length == 0 {
+            return 0;
+        }
+        (length * self.num / self.den).max(1)
+    }
+
+    pub fn point(&self, x: f32, y: f32) -> (i32, i32) {
+        let scale = self.num as f32 / self.den as f32;
+        (self.x + (x * scale) as i32, self.y + (y * scale) as i32)
+    }
+
+    /// Top left corner and size of the field on the screen
+    pub fn field(&self, field: (u32, u32)) -> ((i32, i32), (u32, u32)) {
+        (
+            (self.x, self.y),
+            (self.length(field.0), self.length(field.1)),
+        )
+    }
+
+    /// Top left corner and diameter of the ball on the screen
+    pub fn ball(&self, ball: &Ball) -> ((i32, i32), u32) {
+        let r = ball.r as f32;
+        (self.point(ball.x - r, ball.y - r), self.length(ball.r * 2))
+    }
+
+    /// Top left corner and size of the paddle on the screen
+    pub fn player(&self, player: &Player) -> ((i32, i32), (u32, u32)) {
+        (
+            self.point(player.x as f32, player.y as f32),
+            (self.length(player.width), self.length(player.height)),
+        )
+    }
+}